@@ -0,0 +1,201 @@
+use std::{fmt, str::FromStr};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::Rules;
+
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+pub enum Colors {
+    Empty,
+    Red,
+    Blue,
+    Green,
+    Yellow,
+    Black,
+    White,
+    Orange,
+    Purple,
+    Cyan,
+    Brown,
+    Pink,
+    Blank,
+    Secret,
+}
+
+impl Colors {
+    /// The letter a color is written as in text, `.` for an empty slot.
+    pub fn to_char(self) -> char {
+        match self {
+            Colors::Empty => '.',
+            Colors::Red => 'R',
+            Colors::Blue => 'B',
+            Colors::Green => 'G',
+            Colors::Yellow => 'Y',
+            Colors::Black => 'K',
+            Colors::White => 'W',
+            Colors::Orange => 'O',
+            Colors::Purple => 'V',
+            Colors::Cyan => 'C',
+            Colors::Brown => 'N',
+            Colors::Pink => 'P',
+            Colors::Blank => '_',
+            Colors::Secret => '?',
+        }
+    }
+
+    /// Reads back a peg color written by `to_char`, ignoring case.
+    pub fn from_char(c: char) -> Option<Colors> {
+        let c = c.to_ascii_uppercase();
+        PEG_COLORS
+            .iter()
+            .chain(&[Colors::Blank])
+            .copied()
+            .find(|color| color.to_char() == c)
+    }
+}
+
+/// Every color a peg of a code can have, in the order palettes are cut from.
+/// `Empty` (a slot not filled yet) and `Secret` (a covered peg) are no peg
+/// colors and can never be part of a code. `Blank` (a hole left on purpose)
+/// is only added to the palette if the rules allow blanks.
+pub const PEG_COLORS: [Colors; 11] = [
+    Colors::Red,
+    Colors::Blue,
+    Colors::Green,
+    Colors::Yellow,
+    Colors::Black,
+    Colors::White,
+    Colors::Orange,
+    Colors::Purple,
+    Colors::Cyan,
+    Colors::Brown,
+    Colors::Pink,
+];
+
+/// A row of pegs, used for the secret as well as for every guess.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Code {
+    pegs: Vec<Colors>,
+}
+
+impl Code {
+    pub fn new(pegs: Vec<Colors>) -> Code {
+        Code { pegs }
+    }
+
+    pub fn pegs(&self) -> &[Colors] {
+        &self.pegs
+    }
+
+    pub fn len(&self) -> usize {
+        self.pegs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pegs.is_empty()
+    }
+
+    /// Whether any color appears more than once.
+    pub fn has_duplicates(&self) -> bool {
+        self.pegs
+            .iter()
+            .enumerate()
+            .any(|(i, peg)| self.pegs[i + 1..].contains(peg))
+    }
+
+    /// Draws every peg uniformly from the playable colors. Without duplicates
+    /// the pegs are drawn without replacement, which needs a validated `rules`.
+    pub fn random<R: Rng>(rules: &Rules, rng: &mut R) -> Code {
+        let mut playable_colors = rules.playable_colors();
+        if rules.allow_duplicates {
+            Code::new(
+                (0..rules.code_length)
+                    .map(|_| *playable_colors.choose(rng).unwrap())
+                    .collect(),
+            )
+        } else {
+            let (pegs, _) = playable_colors.partial_shuffle(rng, rules.code_length);
+            Code::new(pegs.to_vec())
+        }
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for peg in &self.pegs {
+            write!(f, "{}", peg.to_char())?;
+        }
+        Ok(())
+    }
+}
+
+/// A letter in a written code that is no peg color.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct ParseCodeError(pub char);
+
+impl fmt::Display for ParseCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is no peg color", self.0)
+    }
+}
+
+impl std::error::Error for ParseCodeError {}
+
+/// Parses a code written one letter per peg, e.g. `RGBY`.
+impl FromStr for Code {
+    type Err = ParseCodeError;
+
+    fn from_str(s: &str) -> Result<Code, ParseCodeError> {
+        s.chars()
+            .map(|c| Colors::from_char(c).ok_or(ParseCodeError(c)))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Code::new)
+    }
+}
+
+impl From<Vec<Colors>> for Code {
+    fn from(pegs: Vec<Colors>) -> Code {
+        Code::new(pegs)
+    }
+}
+
+/// The validation pins for one guess: `black` pegs have the right color in the
+/// right position, `white` pegs have the right color in the wrong position.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub struct Feedback {
+    pub black: usize,
+    pub white: usize,
+}
+
+// `Secret` is the last variant
+const NUMBER_OF_COLORS: usize = Colors::Secret as usize + 1;
+
+/// Scores `guess` against `secret`, both codes need the same length.
+pub fn score(secret: &Code, guess: &Code) -> Feedback {
+    assert_eq!(
+        secret.len(),
+        guess.len(),
+        "secret and guess need the same length"
+    );
+    // indexed by color, solvers call this a lot so it must not allocate
+    let mut color_occurences_secret = [0; NUMBER_OF_COLORS];
+    let mut color_occurences_guess = [0; NUMBER_OF_COLORS];
+    let mut black = 0;
+
+    for (s, g) in secret.pegs().iter().zip(guess.pegs()) {
+        if s == g {
+            black += 1;
+        } else {
+            color_occurences_secret[*s as usize] += 1;
+            color_occurences_guess[*g as usize] += 1;
+        }
+    }
+
+    let white = color_occurences_secret
+        .iter()
+        .zip(&color_occurences_guess)
+        .map(|(s, g)| usize::min(*s, *g))
+        .sum();
+
+    Feedback { black, white }
+}
@@ -0,0 +1,156 @@
+use std::{fs, io};
+
+use chrono::{Datelike, Local, NaiveDate};
+use mastermind::{Code, Rules};
+
+use crate::{options::Options, storage};
+
+const DAILY_FILE: &str = "daily.txt";
+
+/// Today's shared puzzle. Everyone playing the same rules on the same date
+/// gets the same secret, and each daily can only be played once: the guesses
+/// are stored as they are played, so quitting doesn't give another try.
+pub struct Daily {
+    date: NaiveDate,
+    rules: Rules,
+}
+
+impl Daily {
+    pub fn today(rules: Rules) -> Daily {
+        Daily {
+            date: Local::now().date_naive(),
+            rules,
+        }
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// The seed only depends on the date and the rules, never on the machine.
+    pub fn seed(&self) -> u64 {
+        let days = self.date.num_days_from_ce() as u64;
+        (days << 16)
+            | (self.rules.code_length as u64) << 8
+            | (self.rules.number_of_colors as u64) << 2
+            | (self.rules.allow_blanks as u64) << 1
+            | self.rules.allow_duplicates as u64
+    }
+
+    /// A started daily goes on with the number of guesses it was started
+    /// with, whatever `--guesses` says now.
+    pub fn apply(&self, options: &mut Options) {
+        if let Some((number_of_guesses, _)) = self.load_progress() {
+            options.number_of_guesses = number_of_guesses;
+        }
+    }
+
+    /// The number of guesses of today's daily and its guesses so far, if it
+    /// was started.
+    pub fn load_progress(&self) -> Option<(usize, Vec<Code>)> {
+        let content = fs::read_to_string(storage::data_file(DAILY_FILE).ok()?).ok()?;
+        let key = self.key();
+        content.lines().find_map(|line| {
+            let mut words = line.split(' ');
+            if words.next()? != key {
+                return None;
+            }
+            let number_of_guesses = words.next()?.parse().ok()?;
+            let guesses = words
+                .next()?
+                .split(',')
+                .map(|guess| {
+                    let guess: Code = guess.parse().ok()?;
+                    (guess.len() == self.rules.code_length).then_some(guess)
+                })
+                .collect::<Option<Vec<Code>>>()?;
+            (guesses.len() <= number_of_guesses).then_some((number_of_guesses, guesses))
+        })
+    }
+
+    /// Stores the guesses of the daily so far in place of the earlier ones,
+    /// one line per daily:
+    /// `<date>/<pegs>x<colors>[u][b] <number of guesses> <guess>,<guess>,...`
+    pub fn record(&self, number_of_guesses: usize, guesses: &[Code]) -> io::Result<()> {
+        let path = storage::data_file(DAILY_FILE)?;
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error),
+        };
+        let prefix = format!("{} ", self.key());
+        let guesses: Vec<String> = guesses.iter().map(Code::to_string).collect();
+        let mut lines: Vec<String> = content
+            .lines()
+            .filter(|line| !line.starts_with(&prefix))
+            .map(str::to_string)
+            .collect();
+        lines.push(format!("{prefix}{number_of_guesses} {}", guesses.join(",")));
+        fs::write(path, lines.join("\n") + "\n")
+    }
+
+    fn key(&self) -> String {
+        let unique = if self.rules.allow_duplicates { "" } else { "u" };
+        let blanks = if self.rules.allow_blanks { "b" } else { "" };
+        format!(
+            "{}/{}x{}{}{}",
+            self.date, self.rules.code_length, self.rules.number_of_colors, unique, blanks
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // other rules than the tests of the game use for today's daily
+    fn rules() -> Rules {
+        Rules {
+            code_length: 5,
+            number_of_colors: 7,
+            ..Rules::default()
+        }
+    }
+
+    #[test]
+    fn progress_reads_back_as_recorded() {
+        storage::use_test_dir();
+        let daily = Daily::today(rules());
+        let guesses: Vec<Code> = ["RGBYK", "RRGGB"]
+            .iter()
+            .map(|guess| guess.parse().unwrap())
+            .collect();
+
+        daily.record(8, &guesses[..1]).unwrap();
+        assert_eq!(daily.load_progress(), Some((8, guesses[..1].to_vec())));
+        daily.record(8, &guesses).unwrap();
+        assert_eq!(daily.load_progress(), Some((8, guesses)));
+
+        let mut options = Options::default();
+        daily.apply(&mut options);
+        assert_eq!(options.number_of_guesses, 8);
+    }
+
+    #[test]
+    fn seed_and_key_only_depend_on_date_and_rules() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let daily = |rules| Daily { date, rules };
+        assert_eq!(daily(rules()).seed(), daily(rules()).seed());
+        assert_eq!(daily(rules()).key(), "2026-10-18/5x7");
+        assert_ne!(daily(rules()).seed(), daily(Rules::default()).seed());
+
+        let other = Rules {
+            allow_duplicates: false,
+            allow_blanks: true,
+            ..rules()
+        };
+        assert_eq!(daily(other).key(), "2026-10-18/5x7ub");
+        assert_ne!(daily(other).seed(), daily(rules()).seed());
+
+        let next_day = Daily {
+            date: date.succ_opt().unwrap(),
+            rules: rules(),
+        };
+        assert_ne!(next_day.seed(), daily(rules()).seed());
+    }
+}
@@ -1,6 +1,11 @@
 use piston_window::{rectangle, types::Color, Context, G2d};
 
+use crate::font::{glyph, GLYPH_HEIGHT, GLYPH_WIDTH};
+
 const BLOCK_SIZE: f64 = 15.0;
+// size of one font pixel, a line of text is one block high
+const TEXT_PIXEL: f64 = 2.0;
+const TEXT_ADVANCE: f64 = (GLYPH_WIDTH + 1) as f64 * TEXT_PIXEL;
 
 fn to_coord(game_coord: i32) -> f64 {
     (game_coord as f64) * BLOCK_SIZE
@@ -10,6 +15,11 @@ pub fn to_coord_u32(game_coord: i32) -> u32 {
     to_coord(game_coord) as u32
 }
 
+/// The block a point in (unscaled) window coordinates lies in.
+pub fn to_game_coord(gui_coord: f64) -> i32 {
+    (gui_coord / BLOCK_SIZE).floor() as i32
+}
+
 pub fn draw_block(color: Color, x: i32, y: i32, con: &Context, g: &mut G2d) {
     let gui_x = to_coord(x);
     let gui_y = to_coord(y);
@@ -35,6 +45,24 @@ pub fn draw_big_block(color: Color, x: i32, y: i32, con: &Context, g: &mut G2d)
     );
 }
 
+/// A big block with a smaller `hole` block in its center.
+pub fn draw_big_hole(rim: Color, hole: Color, x: i32, y: i32, con: &Context, g: &mut G2d) {
+    draw_big_block(rim, x, y, con, g);
+
+    let inset = BLOCK_SIZE / 2.0;
+    rectangle(
+        hole,
+        [
+            to_coord(x) + inset,
+            to_coord(y) + inset,
+            BLOCK_SIZE,
+            BLOCK_SIZE,
+        ],
+        con.transform,
+        g,
+    );
+}
+
 pub fn draw_rectangle(
     color: Color,
     x: i32,
@@ -59,3 +87,47 @@ pub fn draw_rectangle(
         g,
     );
 }
+
+/// Draws a single line of text with its top left corner at the block `x`, `y`.
+pub fn draw_text(color: Color, text: &str, x: i32, y: i32, con: &Context, g: &mut G2d) {
+    draw_text_at(color, text, to_coord(x), y, con, g);
+}
+
+/// Draws a single line of text in row `y`, centered in the first `width` blocks.
+pub fn draw_text_centered(
+    color: Color,
+    text: &str,
+    width: i32,
+    y: i32,
+    con: &Context,
+    g: &mut G2d,
+) {
+    // the advance of the last character is spacing only
+    let text_width = text.chars().count() as f64 * TEXT_ADVANCE - TEXT_PIXEL;
+    draw_text_at(color, text, (to_coord(width) - text_width) / 2.0, y, con, g);
+}
+
+fn draw_text_at(color: Color, text: &str, gui_x: f64, y: i32, con: &Context, g: &mut G2d) {
+    let gui_y = to_coord(y) + (BLOCK_SIZE - GLYPH_HEIGHT as f64 * TEXT_PIXEL) / 2.0;
+
+    for (i, c) in text.chars().enumerate() {
+        let gui_x = gui_x + i as f64 * TEXT_ADVANCE;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - column)) != 0 {
+                    rectangle(
+                        color,
+                        [
+                            gui_x + column as f64 * TEXT_PIXEL,
+                            gui_y + row as f64 * TEXT_PIXEL,
+                            TEXT_PIXEL,
+                            TEXT_PIXEL,
+                        ],
+                        con.transform,
+                        g,
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,14 +1,37 @@
-use std::process;
+use std::{
+    io,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    draw::{draw_big_block, draw_block, draw_rectangle},
-    guess, COLOR_GAMEOVER, COLOR_SUCCESS, FIELD_SIZE, SPACING,
+    daily::Daily,
+    draw::{draw_block, draw_rectangle, draw_text_centered, to_game_coord},
+    guess, help,
+    history::{self, Played, Record, Setup, Statistics},
+    net::{Connection, Message, Outcome},
+    options::{Assist, Frontend, Mode, Network, Options},
+    replay::Replay,
+    save::SavedGame,
+    COLOR_GAMEOVER, COLOR_OVERLAY, COLOR_RED, COLOR_SUCCESS, COLOR_TEXT, FIELD_SIZE, SPACING,
+};
+use guess::{draw_peg, DrawColor, GuessInputField, PALETTE_HEIGHT};
+use mastermind::{
+    score,
+    solver::{Solver, SolverError},
+    Code, Colors, Feedback, Rules,
 };
-use guess::{Colors, GuessInputField};
 use piston_window::{Context, G2d, Key};
-use rand::{thread_rng, Rng};
+use rand::{rngs::StdRng, SeedableRng};
 
 const MOVING_PERIOD: f64 = 0.1;
+// seconds the solver takes for each guess in codemaker mode
+const SOLVER_DELAY: f64 = 1.0;
+// the window is never smaller than the standard board, so text always fits
+const MIN_WIDTH: i32 = 24;
+const MIN_HEIGHT: i32 = 39;
+// lines of text below the input field
+const STATUS_LINES: i32 = 2;
 
 #[derive(Clone, Copy)]
 struct SecretField {
@@ -21,9 +44,9 @@ struct SecretField {
 impl SecretField {
     pub fn draw(&self, con: &Context, g: &mut G2d) {
         if self.show_color {
-            draw_big_block(self.color.return_color(), self.x, self.y, con, g);
+            draw_peg(self.color, self.x, self.y, con, g);
         } else {
-            draw_big_block(Colors::Secret.return_color(), self.x, self.y, con, g);
+            draw_peg(Colors::Secret, self.x, self.y, con, g);
         }
     }
 
@@ -40,7 +63,11 @@ struct GuessField {
 
 impl GuessField {
     pub fn draw(&self, con: &Context, g: &mut G2d) {
-        draw_big_block(self.color.return_color(), self.x, self.y, con, g);
+        draw_peg(self.color, self.x, self.y, con, g);
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        (self.x..self.x + FIELD_SIZE).contains(&x) && (self.y..self.y + FIELD_SIZE).contains(&y)
     }
 }
 
@@ -49,21 +76,26 @@ struct ValidationField {
     x: i32,
     y: i32,
     pins: Vec<Colors>,
+    feedback: Feedback,
 }
 
 impl ValidationField {
     pub fn draw(&self, con: &Context, g: &mut G2d) {
-        draw_block(self.pins[0].return_color(), self.x, self.y, con, g);
-        draw_block(self.pins[1].return_color(), self.x + 1, self.y, con, g);
-        draw_block(self.pins[2].return_color(), self.x, self.y + 1, con, g);
-        draw_block(self.pins[3].return_color(), self.x + 1, self.y + 1, con, g);
+        // pins are laid out in two rows
+        let columns = ValidationField::columns(self.pins.len());
+        for (i, pin) in self.pins.iter().enumerate() {
+            let (column, row) = ((i % columns) as i32, (i / columns) as i32);
+            draw_block(pin.return_color(), self.x + column, self.y + row, con, g);
+        }
     }
 
-    pub fn set_pins(&mut self, black_pins: i32, white_pins: i32) {
+    pub fn set_pins(&mut self, feedback: Feedback) {
+        let size = self.pins.len();
+        self.feedback = feedback;
         self.pins = vec![];
-        let mut b = black_pins;
-        let mut w = white_pins;
-        for _ in 0..4 {
+        let mut b = feedback.black;
+        let mut w = feedback.white;
+        for _ in 0..size {
             if b > 0 {
                 self.pins.push(Colors::Black);
                 b -= 1;
@@ -75,6 +107,33 @@ impl ValidationField {
             }
         }
     }
+
+    fn columns(code_length: usize) -> usize {
+        code_length.div_ceil(2)
+    }
+}
+
+/// Everything a frontend without piston needs to show the game.
+pub struct View {
+    /// Pegs that are not revealed yet are `Colors::Secret`.
+    pub secret: Vec<Colors>,
+    /// The pegs and the pins of every row, empty rows included.
+    pub rows: Vec<(Vec<Colors>, Vec<Colors>)>,
+    /// Rows marked red at the side of the board.
+    pub marked_rows: Vec<usize>,
+    /// Shown instead of the board while it is covered.
+    pub cover: Option<String>,
+    pub input: Vec<Colors>,
+    /// The selected input field, `input.len()` for the enter block.
+    pub position: usize,
+    pub ready: bool,
+    pub palette: Vec<Colors>,
+    pub status: String,
+    pub status_detail: String,
+    pub won: bool,
+    pub over: bool,
+    /// The help or statistics screen, shown instead of the board.
+    pub overlay: Option<Vec<String>>,
 }
 
 pub struct Game {
@@ -82,66 +141,290 @@ pub struct Game {
 
     width: i32,
     height: i32,
+    status_y: i32,
 
+    rules: Rules,
     number_of_guesses: usize,
     secret: Vec<SecretField>,
     guessed: Vec<Vec<GuessField>>,
     guess_validation: Vec<ValidationField>,
     guess_pointer: usize,
+    // how many rows back the last copied guess was
+    copy_offset: usize,
+    // hints count against the score
+    hints_used: usize,
+    hints_this_guess: usize,
+    // shown in the status line until the next guess, like the last hint
+    message: Option<String>,
+    assist: Assist,
+    // the guess held back by the assist and the rows it contradicts
+    contradiction: Option<(Code, Vec<usize>)>,
+
+    mode: Mode,
+    // in codemaker mode the player enters the secret with the input field
+    entering_secret: bool,
+    secret_rejected: bool,
+    solver: Option<Solver>,
+    solver_time: f64,
+    // the solver's guess waiting for the player's pins, with the pins so far
+    pending_guess: Option<(Code, Feedback)>,
+    // rows the player gave the wrong pins for
+    wrong_pins: Vec<usize>,
+    // in hot seat mode, the index of the player who sets the secret and the
+    // points of both players
+    codemaker: usize,
+    match_score: [usize; 2],
+
+    // a race over the network: the connection, which side of it this is, the
+    // rival's guesses with its last feedback and how the race ended
+    connection: Option<Connection>,
+    network: Option<Network>,
+    rival: (usize, Feedback),
+    race: Option<Outcome>,
+    // the client's guess the host is scoring
+    awaiting_feedback: Option<Code>,
+    // a recorded game shown instead of playing one
+    replay: Option<Replay>,
 
     game_over: bool,
     game_won: bool,
+    show_help: bool,
+    // the lines of the statistics screen while it is shown
+    statistics: Option<Vec<String>>,
 
     waiting_time: f64,
+    // when the secret was set, for the history
+    started: Instant,
+    // while the guesses of an earlier session are played again, its end was
+    // already logged and recorded
+    restoring: bool,
+    // once the game is saved the save follows every guess, and it is removed
+    // when the game ends, so it can't be resumed to try again
+    saved: bool,
 
+    seed: u64,
+    round: usize,
+    daily: Option<Daily>,
+    rng: StdRng,
     debug: bool,
+    frontend: Frontend,
+    log_lines: Vec<String>,
 }
 
 impl Game {
-    pub fn new(width: i32, height: i32, number_of_guesses: usize, debug: bool) -> Game {
+    pub fn new(options: &Options, connection: Option<Connection>) -> Game {
+        let rules = options.rules;
+        let number_of_guesses = options.number_of_guesses;
+        let debug = options.debug;
+        let (width, height) = Game::window_size(&rules, number_of_guesses);
         let (gui_position_x, gui_position_y) =
-            Game::calculate_guess_input_position(width, height, number_of_guesses as i32);
-        println!("{}", gui_position_y);
-        let secret = Game::create_new_secret(debug);
-        let guessed = Game::create_empty_guessed(number_of_guesses);
-        let guess_validation = Game::create_empty_guess_validation(number_of_guesses);
-
-        Game {
-            guess_input_field: GuessInputField::new(gui_position_x, gui_position_y),
+            Game::calculate_guess_input_position(number_of_guesses as i32);
+        let daily = options.daily.then(|| Daily::today(rules));
+        // without a seed pick one, so every game can be replayed
+        let seed = match &daily {
+            Some(daily) => daily.seed(),
+            None => options.seed.unwrap_or_else(rand::random),
+        };
+        let mut rng = StdRng::seed_from_u64(seed);
+        let secret = match options.network {
+            // only the host knows it
+            Some(Network::Connect(_)) => Game::unknown_secret(rules.code_length),
+            _ => Game::create_new_secret(options.mode, &rules, &mut rng, debug),
+        };
+        let guessed = Game::create_empty_guessed(rules.code_length, number_of_guesses);
+        let guess_validation =
+            Game::create_empty_guess_validation(rules.code_length, number_of_guesses);
+
+        let guess_input_field = GuessInputField::new(gui_position_x, gui_position_y, &rules);
+        let mut game = Game {
+            status_y: guess_input_field.bottom() + 1,
+            guess_input_field,
             width,
             height,
+            rules,
             number_of_guesses,
             secret,
             guessed,
             guess_validation,
             guess_pointer: 0,
+            copy_offset: 0,
+            hints_used: 0,
+            hints_this_guess: 0,
+            message: None,
+            assist: options.assist,
+            contradiction: None,
+            mode: options.mode,
+            entering_secret: options.mode != Mode::Codebreaker,
+            secret_rejected: false,
+            solver: None,
+            solver_time: 0.0,
+            pending_guess: None,
+            wrong_pins: vec![],
+            codemaker: 0,
+            match_score: [0, 0],
+            connection,
+            network: options.network.clone(),
+            rival: (0, Feedback::default()),
+            race: None,
+            awaiting_feedback: None,
+            replay: None,
             game_over: false,
             game_won: false,
+            show_help: false,
+            statistics: None,
             waiting_time: 0.0,
+            started: Instant::now(),
+            restoring: false,
+            saved: false,
+            seed,
+            round: 1,
+            daily: None,
+            rng,
             debug,
+            frontend: options.frontend,
+            log_lines: vec![],
+        };
+        game.log("started");
+        if let Some(daily) = daily {
+            // a started daily goes on where it was left, a finished one
+            // only shows its result
+            if let Some((_, guesses)) = daily.load_progress() {
+                game.restoring = true;
+                for guess in guesses.iter().take(game.number_of_guesses) {
+                    let feedback = game.check_guess_against_secret(guess);
+                    game.set_validation_pins_and_save_guess(feedback, guess);
+                }
+                game.restoring = false;
+                game.log(match game.game_over || game.game_won {
+                    true => "today's daily was already played",
+                    false => "today's daily goes on",
+                });
+            }
+            game.daily = Some(daily);
+        }
+        game
+    }
+
+    pub fn view(&self) -> View {
+        let secret = self
+            .secret
+            .iter()
+            .map(|field| match field.show_color {
+                true => field.color,
+                false => Colors::Secret,
+            })
+            .collect();
+        let rows = self
+            .guessed
+            .iter()
+            .zip(&self.guess_validation)
+            .map(|(row, validation)| {
+                let pegs = row.iter().map(|field| field.color).collect();
+                (pegs, validation.pins.clone())
+            })
+            .collect();
+        View {
+            secret,
+            rows,
+            marked_rows: self.marked_rows(),
+            cover: self.cover(),
+            input: self.guess_input_field.get_guess().clone(),
+            position: self.guess_input_field.position(),
+            ready: self.guess_input_field.is_ready(),
+            palette: self.guess_input_field.palette().to_vec(),
+            status: self.status(),
+            status_detail: self.status_detail(),
+            won: self.game_won,
+            over: self.game_over,
+            overlay: self.overlay(),
+        }
+    }
+
+    /// The lines of the help or statistics screen if one is shown.
+    fn overlay(&self) -> Option<Vec<String>> {
+        match self.show_help {
+            true => Some(help::lines(
+                &self.rules,
+                self.number_of_guesses,
+                &self.game_name(),
+            )),
+            false => self.statistics.clone(),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        format!("Mastermind ({})", self.game_name())
+    }
+
+    fn game_name(&self) -> String {
+        match (&self.daily, self.mode) {
+            _ if self.replay.is_some() => "replay".to_string(),
+            _ if self.is_client() => "race".to_string(),
+            _ if self.network.is_some() => format!("race, seed {}", self.seed),
+            (_, Mode::Codemaker { .. }) => "codemaker".to_string(),
+            (_, Mode::HotSeat) => "hot seat".to_string(),
+            (Some(daily), _) => format!("daily {}", daily.date()),
+            (None, _) => format!("seed {}", self.seed),
         }
     }
 
     pub fn update(&mut self, delta_time: f64) {
         self.waiting_time += delta_time;
+        // the host keeps scoring the rival after its own game ended
+        self.update_connection();
 
         if self.game_over {
             return;
         }
 
+        if self.solver.is_some() {
+            self.update_solver(delta_time);
+        }
+
         if self.waiting_time > MOVING_PERIOD {
             self.update_guess_input_field();
         }
     }
 
     pub fn key_pressed(&mut self, key: Key) {
+        // any key closes the help and the statistics
+        if self.show_help || self.statistics.is_some() {
+            self.show_help = false;
+            self.statistics = None;
+            return;
+        }
         match key {
-            Key::H => todo!(),        //print help message
+            Key::H => self.show_help = true, // show help
+            Key::T => self.show_statistics(),
+            _ if self.replay.is_some() => self.replay_key_pressed(key),
             Key::R => self.restart(), // restart game
+            _ if self.pending_guess.is_some() => self.pin_key_pressed(key),
+            Key::P if self.player_breaks() => self.copy_previous_guess(),
+            Key::I if self.player_breaks() => self.give_hint(),
+            Key::S => self.save(),
             key => self.guess_input_field.key_pressed(key),
         }
     }
 
+    /// Handles a left click at `x`, `y` in unscaled window coordinates.
+    pub fn mouse_pressed(&mut self, x: f64, y: f64) {
+        if self.show_help || self.statistics.is_some() {
+            self.show_help = false;
+            self.statistics = None;
+            return;
+        }
+        let (x, y) = (to_game_coord(x), to_game_coord(y));
+        // clicking an earlier guess copies it into the input field
+        let clicked_row = self.guessed[..self.guess_pointer]
+            .iter()
+            .position(|row| row.iter().any(|field| field.contains(x, y)));
+        match clicked_row {
+            Some(row) if self.player_breaks() => self.copy_guess(row),
+            Some(_) => {}
+            None => self.guess_input_field.mouse_pressed(x, y),
+        }
+    }
+
     pub fn draw(&self, con: &Context, g: &mut G2d) {
         if self.game_won {
             draw_rectangle(COLOR_SUCCESS, 0, 0, self.width, self.height, con, g);
@@ -164,18 +447,255 @@ impl Game {
         for field in &self.guess_validation {
             field.draw(con, g);
         }
+        for row in self.marked_rows() {
+            let y = self.guessed[row][0].y;
+            draw_rectangle(COLOR_RED, 0, y, 1, FIELD_SIZE, con, g);
+        }
+        if let Some(cover) = self.cover() {
+            let board_height = self.guessed[self.number_of_guesses - 1][0].y + FIELD_SIZE;
+            draw_rectangle(COLOR_OVERLAY, 0, 0, self.width, board_height, con, g);
+            draw_text_centered(COLOR_TEXT, &cover, self.width, board_height / 2, con, g);
+        }
         self.guess_input_field.draw(con, g);
+
+        draw_text_centered(
+            COLOR_TEXT,
+            &self.status(),
+            self.width,
+            self.status_y,
+            con,
+            g,
+        );
+        draw_text_centered(
+            COLOR_TEXT,
+            &self.status_detail(),
+            self.width,
+            self.status_y + 1,
+            con,
+            g,
+        );
+
+        if let Some(lines) = self.overlay() {
+            help::draw(&lines, self.width, self.height, con, g);
+        }
+    }
+
+    /// The rows a held back guess contradicts and the rows the player gave
+    /// the wrong pins for.
+    fn marked_rows(&self) -> Vec<usize> {
+        let contradicted = self.contradiction.iter().flat_map(|(_, rows)| rows);
+        contradicted.chain(&self.wrong_pins).copied().collect()
+    }
+
+    /// What covers the board, the codebreaker must not see the secret being
+    /// entered.
+    fn cover(&self) -> Option<String> {
+        (self.mode == Mode::HotSeat && self.entering_secret)
+            .then(|| format!("PLAYER {}, LOOK AWAY", 2 - self.codemaker))
+    }
+
+    /// The line below the input field: how far the game is, or how it ended.
+    fn status(&self) -> String {
+        if let Some(replay) = &self.replay {
+            return replay_status(replay);
+        }
+        match self.mode {
+            Mode::Codemaker { .. } => return self.codemaker_status(),
+            Mode::HotSeat => return self.hot_seat_status(),
+            Mode::Codebreaker => {}
+        }
+        if let Some(outcome) = self.race {
+            match outcome {
+                Outcome::Won => "YOU WON THE RACE!",
+                Outcome::Lost => "YOUR RIVAL WAS FASTER",
+                Outcome::Draw => "NOBODY CRACKED IT",
+            }
+            .to_string()
+        } else if self.awaiting_feedback.is_some() {
+            "WAITING FOR THE HOST".to_string()
+        } else if self.game_over && self.connection.is_some() {
+            "WAITING FOR YOUR RIVAL".to_string()
+        } else if self.game_won {
+            match (self.guess_pointer, self.hints_used) {
+                (1, 0) => "YOU WON IN 1 GUESS!".to_string(),
+                (n, 0) => format!("YOU WON IN {n} GUESSES!"),
+                (n, hints) => format!(
+                    "WON IN {n} {}, {hints} {}",
+                    plural(n, "GUESS", "GUESSES"),
+                    plural(hints, "HINT", "HINTS")
+                ),
+            }
+        } else if self.game_over {
+            "GAME OVER".to_string()
+        } else if let Some((_, rows)) = &self.contradiction {
+            contradiction_status(rows)
+        } else if let Some(message) = &self.message {
+            message.clone()
+        } else {
+            format!(
+                "GUESS {} OF {}, {} LEFT",
+                self.guess_pointer + 1,
+                self.number_of_guesses,
+                self.number_of_guesses - self.guess_pointer
+            )
+        }
+    }
+
+    /// The line below the status: what to do about a held back guess, or
+    /// which game is played.
+    fn status_detail(&self) -> String {
+        if let Some(replay) = &self.replay {
+            return replay_status_detail(replay);
+        }
+        match self.mode {
+            Mode::Codemaker { .. } => return self.codemaker_status_detail(),
+            Mode::HotSeat => return self.hot_seat_status_detail(),
+            Mode::Codebreaker => {}
+        }
+        match (&self.contradiction, self.assist) {
+            (Some(_), Assist::Warn) => "SUBMIT AGAIN TO PLAY IT".to_string(),
+            (Some(_), _) => "IT CAN'T BE THE SECRET".to_string(),
+            (None, _) if self.network.is_some() => self.rival_status(),
+            (None, _) => self.game_name().to_uppercase(),
+        }
+    }
+
+    fn rival_status(&self) -> String {
+        let (guesses, feedback) = self.rival;
+        match (&self.connection, &self.network) {
+            (None, Some(Network::Connect(_))) => "THE HOST LEFT".to_string(),
+            (None, _) => "YOUR RIVAL LEFT".to_string(),
+            _ => format!(
+                "RIVAL: {guesses} {}, {} BLACK",
+                plural(guesses, "GUESS", "GUESSES"),
+                feedback.black
+            ),
+        }
+    }
+
+    fn codemaker_status(&self) -> String {
+        if self.entering_secret {
+            "ENTER YOUR SECRET".to_string()
+        } else if self.game_won {
+            let n = self.guess_pointer;
+            format!("SOLVER WON IN {n} {}", plural(n, "GUESS", "GUESSES"))
+        } else if self.game_over {
+            "YOU BEAT THE SOLVER!".to_string()
+        } else if self.pending_guess.is_some() {
+            "PINS: B, W, THEN RETURN".to_string()
+        } else {
+            format!(
+                "SOLVER GUESS {} OF {}",
+                self.guess_pointer + 1,
+                self.number_of_guesses
+            )
+        }
+    }
+
+    fn codemaker_status_detail(&self) -> String {
+        if self.entering_secret && self.secret_rejected {
+            "NO COLOR MAY REPEAT".to_string()
+        } else if self.entering_secret {
+            "THE SOLVER WILL CRACK IT".to_string()
+        } else if let Some((_, pins)) = &self.pending_guess {
+            format!("{} BLACK, {} WHITE", pins.black, pins.white)
+        } else if !self.wrong_pins.is_empty() {
+            let n = self.wrong_pins.len();
+            format!("{n} WRONG {}", plural(n, "SCORE", "SCORES"))
+        } else {
+            "CODEMAKER".to_string()
+        }
+    }
+
+    fn hot_seat_status(&self) -> String {
+        let (maker, breaker) = (self.codemaker + 1, 2 - self.codemaker);
+        if self.entering_secret {
+            format!("PLAYER {maker}: SET A SECRET")
+        } else if self.game_won || self.game_over {
+            let verdict = match self.game_won {
+                true => "CRACKED",
+                false => "NOT CRACKED",
+            };
+            format!("{verdict}, P{maker} +{}", self.codemaker_points())
+        } else if let Some((_, rows)) = &self.contradiction {
+            contradiction_status(rows)
+        } else if let Some(message) = &self.message {
+            message.clone()
+        } else {
+            format!(
+                "PLAYER {breaker}: GUESS {} OF {}",
+                self.guess_pointer + 1,
+                self.number_of_guesses
+            )
+        }
+    }
+
+    fn hot_seat_status_detail(&self) -> String {
+        let score = format!("P1 {} : P2 {}", self.match_score[0], self.match_score[1]);
+        if self.entering_secret && self.secret_rejected {
+            "NO COLOR MAY REPEAT".to_string()
+        } else if self.game_won || self.game_over {
+            format!("{score}, R: NEXT ROUND")
+        } else if self.contradiction.is_some() && self.assist == Assist::Warn {
+            "SUBMIT AGAIN TO PLAY IT".to_string()
+        } else {
+            score
+        }
+    }
+
+    /// Whether the player is the codebreaker right now.
+    fn player_breaks(&self) -> bool {
+        if self.replay.is_some() {
+            return false;
+        }
+        match self.mode {
+            Mode::Codebreaker => true,
+            Mode::Codemaker { .. } => false,
+            Mode::HotSeat => !self.entering_secret,
+        }
+    }
+
+    /// What the codemaker scored in a hot seat round: a point for every guess
+    /// and hint the codebreaker needed, and one more if the code wasn't
+    /// cracked.
+    fn codemaker_points(&self) -> usize {
+        self.guess_pointer + self.hints_used + usize::from(!self.game_won)
     }
 
     fn restart(&mut self) {
+        // there is only one daily per day, and one secret per race
+        if self.daily.is_some() || self.network.is_some() || self.replay.is_some() {
+            return;
+        }
+        // after a finished round the players swap roles, otherwise the same
+        // codemaker sets a new secret
+        if self.mode == Mode::HotSeat && (self.game_won || self.game_over) {
+            self.codemaker = 1 - self.codemaker;
+        }
         //reset guess pointer
         self.guess_pointer = 0;
+        self.copy_offset = 0;
+        self.hints_used = 0;
+        self.hints_this_guess = 0;
+        self.message = None;
+        self.contradiction = None;
+        self.entering_secret = self.mode != Mode::Codebreaker;
+        self.secret_rejected = false;
+        self.solver = None;
+        self.pending_guess = None;
+        self.wrong_pins.clear();
         // reset guessed
-        self.guessed = Game::create_empty_guessed(self.number_of_guesses);
+        self.guessed = Game::create_empty_guessed(self.rules.code_length, self.number_of_guesses);
         // reset validation
-        self.guess_validation = Game::create_empty_guess_validation(self.number_of_guesses);
+        self.guess_validation =
+            Game::create_empty_guess_validation(self.rules.code_length, self.number_of_guesses);
         // create new secret
-        self.secret = Game::create_new_secret(self.debug);
+        self.secret = Game::create_new_secret(self.mode, &self.rules, &mut self.rng, self.debug);
+        self.round += 1;
+        self.started = Instant::now();
+        // the save of the earlier round stays
+        self.saved = false;
+        self.log("started");
         // reset gameover / success
         self.game_over = false;
         self.game_won = false;
@@ -185,178 +705,902 @@ impl Game {
         self.guess_input_field.enable_input();
     }
 
+    /// Copies the last guess, pressing again walks further back.
+    fn copy_previous_guess(&mut self) {
+        if self.guess_pointer == 0 {
+            return;
+        }
+        self.copy_offset = self.copy_offset % self.guess_pointer + 1;
+        self.copy_guess(self.guess_pointer - self.copy_offset);
+    }
+
+    fn copy_guess(&mut self, row: usize) {
+        let guess: Vec<Colors> = self.guessed[row].iter().map(|field| field.color).collect();
+        self.guess_input_field.set_guess(&guess);
+    }
+
+    /// Saves the game to be resumed with `--resume`, only games against a
+    /// secret from the seed can be.
+    fn save(&mut self) {
+        let saveable = self.mode == Mode::Codebreaker
+            && self.daily.is_none()
+            && self.network.is_none()
+            && !self.game_won
+            && !self.game_over;
+        if !saveable {
+            self.message = Some("THIS GAME CAN'T BE SAVED".to_string());
+            return;
+        }
+        match self.store_save() {
+            Ok(path) => {
+                self.saved = true;
+                self.message = Some("GAME SAVED".to_string());
+                self.log(&format!("saved to {}", path.display()));
+            }
+            Err(error) => {
+                self.message = Some("SAVING FAILED".to_string());
+                self.log(&format!("could not save: {error}"));
+            }
+        }
+    }
+
+    fn store_save(&self) -> io::Result<PathBuf> {
+        SavedGame::new(
+            Setup::new(&self.rules, self.number_of_guesses),
+            self.seed,
+            self.round,
+            &self.secret_code(),
+            &self.rows(),
+            self.hints_used,
+            self.started.elapsed().as_secs(),
+        )
+        .store()
+    }
+
+    /// Continues a saved game, `Game::new` must have been given the saved
+    /// rules and seed.
+    pub fn resume(&mut self, saved: &SavedGame) -> Result<(), String> {
+        let secret = saved.secret()?;
+        let guesses = saved.rows()?;
+        if guesses.len() >= self.number_of_guesses {
+            return Err("the saved game is already over".to_string());
+        }
+        // draw the secrets the saved session drew, so restarting goes on
+        // with the same ones
+        for _ in 1..saved.round {
+            Code::random(&self.rules, &mut self.rng);
+        }
+        self.round = saved.round;
+        self.secret = Game::secret_fields(&secret, self.debug);
+        self.hints_used = saved.hints_used;
+        // the time played before saving counts too
+        let played = Duration::from_secs(saved.seconds);
+        self.started = Instant::now().checked_sub(played).unwrap_or(self.started);
+        self.restoring = true;
+        for guess in &guesses {
+            let feedback = self.check_guess_against_secret(guess);
+            self.set_validation_pins_and_save_guess(feedback, guess);
+        }
+        self.restoring = false;
+        if self.game_won {
+            return Err("the saved game is already over".to_string());
+        }
+        self.log("resumed");
+        Ok(())
+    }
+
+    /// Shows the recorded game from its first guess on, the input is
+    /// disabled while it is replayed.
+    pub fn start_replay(&mut self, replay: Replay) {
+        self.log(&format!(
+            "replaying the game of {}, secret {}",
+            replay.time(),
+            replay.secret()
+        ));
+        self.guess_input_field.disable_input();
+        self.replay = Some(replay);
+        self.show_replay_step();
+    }
+
+    fn replay_key_pressed(&mut self, key: Key) {
+        let Some(replay) = &mut self.replay else {
+            return;
+        };
+        match key {
+            Key::Right | Key::Down | Key::Return | Key::Space => replay.forward(),
+            Key::Left | Key::Up | Key::Backspace => replay.back(),
+            Key::I => replay.toggle_insight(),
+            _ => return,
+        }
+        self.show_replay_step();
+    }
+
+    /// Draws the board as it was after the guesses of the current step,
+    /// with the secret revealed once all of them are shown.
+    fn show_replay_step(&mut self) {
+        let Some(replay) = &self.replay else {
+            return;
+        };
+        let rows = replay.shown().to_vec();
+        let secret = replay.secret().clone();
+        let at_end = replay.at_end();
+        let cracked = replay.cracked();
+
+        self.guessed = Game::create_empty_guessed(self.rules.code_length, self.number_of_guesses);
+        self.guess_validation =
+            Game::create_empty_guess_validation(self.rules.code_length, self.number_of_guesses);
+        for (row, (guess, feedback)) in rows.iter().enumerate() {
+            self.show_guess(row, guess);
+            self.guess_validation[row].set_pins(*feedback);
+        }
+        self.guess_pointer = rows.len();
+        self.secret = Game::secret_fields(&secret, at_end || self.debug);
+        self.game_won = at_end && cracked;
+        self.game_over = at_end && !cracked;
+    }
+
+    /// The first hint for a guess tells how many secrets are still possible,
+    /// every further one fills in the next of them.
+    fn give_hint(&mut self) {
+        if self.game_over || self.game_won || self.entering_secret {
+            return;
+        }
+        let candidates = match self.candidates() {
+            Ok(candidates) => candidates,
+            Err(SolverError::TooManyCodes) => {
+                self.message = Some("TOO MANY CODES FOR HINTS".to_string());
+                return;
+            }
+        };
+        let count = candidates.len();
+        if self.hints_this_guess == 0 {
+            self.message = Some(format!(
+                "{count} {} LEFT",
+                plural(count, "SECRET", "SECRETS")
+            ));
+            self.log(&format!("hint: {count} secrets left"));
+        } else {
+            let candidate = &candidates[(self.hints_this_guess - 1) % count];
+            self.guess_input_field.set_guess(candidate.pegs());
+            self.message = Some(format!("HINT: ONE OF {count} LEFT"));
+            self.log(&format!("hint: {candidate}"));
+        }
+        self.hints_this_guess += 1;
+        self.hints_used += 1;
+    }
+
+    /// Every secret that fits the feedback of all guesses so far.
+    fn candidates(&self) -> Result<Vec<Code>, SolverError> {
+        let mut solver = Solver::new(&self.rules)?;
+        for (guess, validation) in self.guessed_codes().iter().zip(&self.guess_validation) {
+            solver.add_feedback(guess, validation.feedback);
+        }
+        Ok(solver.candidates().to_vec())
+    }
+
     fn update_guess_input_field(&mut self) {
         self.guess_input_field.update();
+        // the marks go away once the held back guess is changed
+        if self
+            .contradiction
+            .as_ref()
+            .is_some_and(|(guess, _)| guess.pegs() != self.guess_input_field.get_guess())
+        {
+            self.contradiction = None;
+        }
         if self.check_send_guess() {
             // get guess
-            let current_guess = self.guess_input_field.get_guess().clone();
+            let current_guess = Code::from(self.guess_input_field.get_guess().clone());
+            if self.entering_secret {
+                self.submit_secret(current_guess);
+                self.waiting_time = 0.0;
+                return;
+            }
+            if !self.assist_accepts(&current_guess) {
+                self.guess_input_field.hold_guess();
+                self.waiting_time = 0.0;
+                return;
+            }
+            self.contradiction = None;
+            // without the host the guess can't be scored
+            if self.is_client() && self.connection.is_none() {
+                self.guess_input_field.hold_guess();
+                self.waiting_time = 0.0;
+                return;
+            }
             // delete guess from input field
             self.guess_input_field.reset_guess();
+            self.copy_offset = 0;
+            self.hints_this_guess = 0;
+            self.message = None;
+            if self.is_client() {
+                // the host scores it, input waits for the feedback
+                self.send(Message::Guess(current_guess.clone()));
+                self.awaiting_feedback = Some(current_guess);
+                self.guess_input_field.disable_input();
+                self.waiting_time = 0.0;
+                return;
+            }
             // run logic on guess
-            let (black_pins, white_pins) = self.check_guess_against_secret(&current_guess);
+            let feedback = self.check_guess_against_secret(&current_guess);
             // set validation pins
             // add guess to guessed
-            self.set_validation_pins_and_save_guess(black_pins, white_pins, &current_guess);
+            self.set_validation_pins_and_save_guess(feedback, &current_guess);
+            if self.network.is_some() {
+                self.send(Message::Rival(self.guess_pointer, feedback));
+                self.settle_race();
+            }
         }
 
         self.waiting_time = 0.0;
     }
 
-    fn set_validation_pins_and_save_guess(
-        &mut self,
-        black_pins: i32,
-        white_pins: i32,
-        current_guess: &Vec<Colors>,
-    ) {
-        self.guess_validation[self.guess_pointer].set_pins(black_pins, white_pins);
+    fn is_client(&self) -> bool {
+        matches!(self.network, Some(Network::Connect(_)))
+    }
 
-        // add guess to guessed
-        for i in 0..4 {
-            self.guessed[self.guess_pointer][i].color = current_guess[i];
+    /// Handles every message that arrived since the last update.
+    fn update_connection(&mut self) {
+        loop {
+            let Some(connection) = &mut self.connection else {
+                return;
+            };
+            match connection.receive() {
+                Ok(Some(message)) => self.handle_message(message),
+                Ok(None) => return,
+                Err(error) if error.kind() == io::ErrorKind::InvalidData => {
+                    self.log(&format!("ignored: {error}"))
+                }
+                Err(error) => return self.lose_connection(error),
+            }
+        }
+    }
+
+    fn handle_message(&mut self, message: Message) {
+        match message {
+            Message::Guess(guess) if !self.is_client() => self.score_rival_guess(guess),
+            Message::Feedback(feedback) if self.is_client() => {
+                let Some(guess) = self.awaiting_feedback.take() else {
+                    return;
+                };
+                if !self.game_over {
+                    self.guess_input_field.enable_input();
+                    self.set_validation_pins_and_save_guess(feedback, &guess);
+                }
+            }
+            Message::Rival(guesses, feedback) if self.is_client() => {
+                self.rival = (guesses, feedback)
+            }
+            Message::Over(outcome, secret) if self.is_client() => {
+                self.end_race(outcome, Some(&secret))
+            }
+            message => self.log(&format!("unexpected message '{message}'")),
+        }
+    }
+
+    /// Scores a guess of the client, the host never trusts it to do so itself.
+    fn score_rival_guess(&mut self, guess: Code) {
+        let (guesses, feedback) = self.rival;
+        let rival_done =
+            feedback.black == self.rules.code_length || guesses == self.number_of_guesses;
+        if self.rules.check_guess(&guess).is_err() || rival_done || self.race.is_some() {
+            self.log(&format!("ignored the rival's guess {guess}"));
+            return;
+        }
+        let feedback = self.check_guess_against_secret(&guess);
+        self.rival = (guesses + 1, feedback);
+        self.send(Message::Feedback(feedback));
+        self.settle_race();
+    }
+
+    /// Decides the race on the host once a side cracked the secret or both
+    /// ran out of guesses, and tells the client.
+    fn settle_race(&mut self) {
+        if self.race.is_some() {
+            return;
+        }
+        let (guesses, feedback) = self.rival;
+        let rival_won = feedback.black == self.rules.code_length;
+        let rival_done = rival_won || guesses == self.number_of_guesses;
+        let outcome = if self.game_won {
+            Outcome::Won
+        } else if rival_won {
+            Outcome::Lost
+        } else if self.game_over && rival_done {
+            Outcome::Draw
+        } else {
+            return;
+        };
+        let secret = self.secret_code();
+        self.end_race(outcome, Some(&secret));
+        self.send(Message::Over(outcome.for_rival(), secret));
+    }
+
+    /// Ends the race with `outcome`, revealing the secret unless the client
+    /// never learned it.
+    fn end_race(&mut self, outcome: Outcome, secret: Option<&Code>) {
+        self.race = Some(outcome);
+        match secret {
+            Some(secret) => {
+                self.log(&format!("race {outcome:?}, the secret was {secret}"));
+                self.secret = Game::secret_fields(secret, true);
+            }
+            None => self.log(&format!("race {outcome:?}, the secret stays unknown")),
+        }
+        match !self.game_won && !self.game_over {
+            true => {
+                self.game_over = true;
+                self.handle_game_end();
+            }
+            // the game ended before the race was decided
+            false => self.record_history(),
         }
+    }
+
+    fn send(&mut self, message: Message) {
+        let Some(connection) = &mut self.connection else {
+            return;
+        };
+        if let Err(error) = connection.send(&message) {
+            self.lose_connection(error);
+        }
+    }
+
+    /// Drops the connection. A race that wasn't decided yet ends with it: the
+    /// rival forfeits, unless this side already ran out of guesses, then
+    /// nobody cracked the secret in time. The client still doesn't know it,
+    /// so it can't record the race.
+    fn lose_connection(&mut self, error: io::Error) {
+        self.log(&format!("lost the connection: {error}"));
+        self.connection = None;
+        if self.race.is_some() {
+            return;
+        }
+        let outcome = match self.game_over && !self.game_won {
+            true => Outcome::Draw,
+            false => Outcome::Won,
+        };
+        let secret = self.secret_known().then(|| self.secret_code());
+        self.end_race(outcome, secret.as_ref());
+    }
+
+    /// Takes the secret entered in codemaker or hot seat mode and starts the
+    /// solver or the other player on it.
+    fn submit_secret(&mut self, secret: Code) {
+        if self.rules.check_secret(&secret).is_err() {
+            self.secret_rejected = true;
+            self.guess_input_field.hold_guess();
+            return;
+        }
+        self.entering_secret = false;
+        self.secret_rejected = false;
+        self.started = Instant::now();
+        self.guess_input_field.reset_guess();
+        if self.mode == Mode::HotSeat {
+            self.secret = Game::secret_fields(&secret, self.debug);
+            self.log(&format!("player {} set the secret", self.codemaker + 1));
+            return;
+        }
+        self.secret = Game::secret_fields(&secret, true);
+        self.guess_input_field.disable_input();
+        self.solver = Some(
+            Solver::new(&self.rules).expect("the options only allow rules the solver can play"),
+        );
+        self.solver_time = 0.0;
+        self.log(&format!("secret {secret} set for the solver"));
+    }
+
+    /// Lets the solver play its next guess once it had time to think.
+    fn update_solver(&mut self, delta_time: f64) {
+        if self.game_won || self.pending_guess.is_some() {
+            return;
+        }
+        self.solver_time += delta_time;
+        if self.solver_time < SOLVER_DELAY {
+            return;
+        }
+        self.solver_time = 0.0;
+        let Some(guess) = self.solver.as_ref().and_then(Solver::next_guess) else {
+            return;
+        };
+        match self.mode {
+            // the guess waits on the board for the player's pins
+            Mode::Codemaker { enter_pins: true } => {
+                self.show_guess(self.guess_pointer, &guess);
+                self.pending_guess = Some((guess, Feedback::default()));
+            }
+            _ => {
+                let feedback = self.check_guess_against_secret(&guess);
+                self.play_solver_guess(&guess, feedback);
+            }
+        }
+    }
+
+    /// Enters the pins for the solver's guess: B and W add a pin, backspace
+    /// takes the last one back and return scores the guess.
+    fn pin_key_pressed(&mut self, key: Key) {
+        let Some((_, pins)) = &mut self.pending_guess else {
+            return;
+        };
+        let full = pins.black + pins.white == self.rules.code_length;
+        match key {
+            Key::B if !full => pins.black += 1,
+            Key::W if !full => pins.white += 1,
+            Key::Backspace if pins.white > 0 => pins.white -= 1,
+            Key::Backspace if pins.black > 0 => pins.black -= 1,
+            Key::Return => return self.score_pending_guess(),
+            _ => return,
+        }
+        let pins = *pins;
+        self.guess_validation[self.guess_pointer].set_pins(pins);
+    }
+
+    /// Plays the solver's guess with the real feedback, flagging the row if
+    /// the player's pins were different.
+    fn score_pending_guess(&mut self) {
+        let Some((guess, pins)) = self.pending_guess.take() else {
+            return;
+        };
+        let feedback = self.check_guess_against_secret(&guess);
+        if pins != feedback {
+            self.log(&format!(
+                "wrong pins for {guess}: {} black {} white instead of {} black {} white",
+                pins.black, pins.white, feedback.black, feedback.white
+            ));
+            self.wrong_pins.push(self.guess_pointer);
+        }
+        self.play_solver_guess(&guess, feedback);
+    }
+
+    fn play_solver_guess(&mut self, guess: &Code, feedback: Feedback) {
+        if let Some(solver) = &mut self.solver {
+            solver.add_feedback(guess, feedback);
+        }
+        self.set_validation_pins_and_save_guess(feedback, guess);
+    }
+
+    /// Whether the assist lets `guess` be played, otherwise it is held back
+    /// with the rows it contradicts.
+    fn assist_accepts(&mut self, guess: &Code) -> bool {
+        if self.assist == Assist::Off {
+            return true;
+        }
+        let rows = self.contradicted_rows(guess);
+        if rows.is_empty() {
+            return true;
+        }
+        // a warning is only given once, the same guess again is played
+        let warned = matches!(&self.contradiction, Some((held, _)) if held == guess);
+        if self.assist == Assist::Warn && warned {
+            self.log(&format!("{guess} played against the warning"));
+            return true;
+        }
+        self.contradiction = Some((guess.clone(), rows));
+        false
+    }
+
+    /// The earlier rows whose feedback would differ if `guess` was the
+    /// secret.
+    fn contradicted_rows(&self, guess: &Code) -> Vec<usize> {
+        self.guessed_codes()
+            .iter()
+            .zip(&self.guess_validation)
+            .enumerate()
+            .filter(|(_, (row, validation))| score(guess, row) != validation.feedback)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn set_validation_pins_and_save_guess(&mut self, feedback: Feedback, current_guess: &Code) {
+        self.guess_validation[self.guess_pointer].set_pins(feedback);
+
+        // add guess to guessed
+        self.show_guess(self.guess_pointer, current_guess);
         self.guess_pointer += 1;
+        let recorded = self
+            .daily
+            .as_ref()
+            .map(|daily| daily.record(self.number_of_guesses, &self.guessed_codes()));
+        if let Some(Err(error)) = recorded {
+            self.log(&format!("could not record the daily: {error}"));
+        }
         // check success or game over
-        if black_pins == 4 {
+        if feedback.black == self.rules.code_length {
             self.game_won = true;
             self.handle_game_end();
         } else if self.guess_pointer == self.number_of_guesses {
             self.game_over = true;
             self.handle_game_end();
+        } else if self.saved {
+            if let Err(error) = self.store_save() {
+                self.log(&format!("could not update the save: {error}"));
+            }
+        }
+    }
+
+    fn show_guess(&mut self, row: usize, guess: &Code) {
+        for (field, color) in self.guessed[row].iter_mut().zip(guess.pegs()) {
+            field.color = *color;
         }
     }
 
     fn handle_game_end(&mut self) {
+        if self.saved {
+            self.saved = false;
+            if let Err(error) = SavedGame::remove() {
+                self.log(&format!("could not remove the save: {error}"));
+            }
+        }
+        if !self.restoring {
+            self.log_result();
+            // a race is recorded once it is decided and the secret is known
+            if self.network.is_none() || self.race.is_some() {
+                self.record_history();
+            }
+        }
         self.guess_input_field.disable_input();
-        for i in 0..4 {
-            self.secret[i].show_color();
+        if self.secret_known() {
+            for field in &mut self.secret {
+                field.show_color();
+            }
         }
     }
 
-    fn check_guess_against_secret(&mut self, current_guess: &Vec<Colors>) -> (i32, i32) {
-        let color_list = Colors::create_color_list();
-        let mut color_occurences_secret = vec![0; color_list.len()];
-        let mut color_occurences_guess = vec![0; color_list.len()];
-        let mut color_occurences_both = vec![0; color_list.len()];
-        let mut c;
-        let mut index;
-
-        for i in 0..4 {
-            c = &self.secret[i].color;
-            index = color_list.iter().position(|x| x == c).unwrap();
-            color_occurences_secret[index] += 1;
+    /// Logs how the game ended, in hot seat mode after adding the points of
+    /// the codemaker to the match.
+    fn log_result(&mut self) {
+        match (self.mode, self.game_won) {
+            (Mode::Codebreaker, true) => self.log(&format!(
+                "won in {} guesses with {} hints",
+                self.guess_pointer, self.hints_used
+            )),
+            (Mode::Codebreaker, false) => self.log("lost"),
+            (Mode::HotSeat, _) => {
+                self.match_score[self.codemaker] += self.codemaker_points();
+                self.log(&format!(
+                    "player {} scores {} ({} guesses, {} hints), match {}:{}",
+                    self.codemaker + 1,
+                    self.codemaker_points(),
+                    self.guess_pointer,
+                    self.hints_used,
+                    self.match_score[0],
+                    self.match_score[1]
+                ));
+            }
+            (_, true) => self.log(&format!(
+                "solver won in {} guesses, {} wrong scores",
+                self.guess_pointer,
+                self.wrong_pins.len()
+            )),
+            (_, false) => self.log(&format!(
+                "solver lost, {} wrong scores",
+                self.wrong_pins.len()
+            )),
+        }
+    }
 
-            c = &current_guess[i];
-            index = color_list.iter().position(|x| x == c).unwrap();
-            color_occurences_guess[index] += 1;
+    /// Appends the finished game to the history, unless its secret is
+    /// unknown.
+    fn record_history(&mut self) {
+        if !self.secret_known() {
+            return;
+        }
+        let played = match (self.mode, &self.daily) {
+            _ if self.network.is_some() => Played::Race,
+            (Mode::Codebreaker, Some(_)) => Played::Daily,
+            (Mode::Codebreaker, None) => Played::Codebreaker,
+            (Mode::Codemaker { .. }, _) => Played::Codemaker,
+            (Mode::HotSeat, _) => Played::HotSeat,
+        };
+        let record = Record::new(
+            played,
+            Setup::new(&self.rules, self.number_of_guesses),
+            &self.secret_code(),
+            &self.rows(),
+            self.hints_used,
+            self.race,
+            self.started.elapsed().as_secs(),
+        );
+        if let Err(error) = history::append(&record) {
+            self.log(&format!("could not record the game: {error}"));
         }
+    }
 
-        for i in 0..color_occurences_both.len() {
-            if color_occurences_guess[i] <= color_occurences_secret[i] {
-                color_occurences_both[i] = color_occurences_guess[i];
-            } else {
-                color_occurences_both[i] = color_occurences_secret[i];
+    fn show_statistics(&mut self) {
+        match history::load() {
+            Ok(records) => {
+                let statistics = Statistics::of(&records, &self.rules);
+                self.statistics = Some(statistics.lines(&self.rules, self.number_of_guesses));
+            }
+            Err(error) => {
+                self.message = Some("NO STATISTICS".to_string());
+                self.log(&format!("could not read the history: {error}"));
             }
         }
+    }
+
+    fn check_guess_against_secret(&self, current_guess: &Code) -> Feedback {
+        score(&self.secret_code(), current_guess)
+    }
 
-        let mut black_pins = 0;
+    fn guessed_codes(&self) -> Vec<Code> {
+        self.guessed[..self.guess_pointer]
+            .iter()
+            .map(|row| Code::new(row.iter().map(|field| field.color).collect()))
+            .collect()
+    }
 
-        for i in 0..4 {
-            if current_guess[i] == self.secret[i].color {
-                black_pins += 1;
-                index = color_list
+    /// The guesses so far with their feedback.
+    fn rows(&self) -> Vec<(Code, Feedback)> {
+        self.guessed_codes()
+            .into_iter()
+            .zip(
+                self.guess_validation
                     .iter()
-                    .position(|x| x == &current_guess[i])
-                    .unwrap();
-                color_occurences_both[index] -= 1;
-            }
+                    .map(|validation| validation.feedback),
+            )
+            .collect()
+    }
+
+    fn secret_code(&self) -> Code {
+        Code::new(self.secret.iter().map(|field| field.color).collect())
+    }
+
+    /// Prints a line to the game log, tagged with what is needed to replay
+    /// the game: the seed and how many secrets were drawn from it. In the
+    /// terminal the line is kept for `take_log` instead, so it doesn't run
+    /// through the board.
+    fn log(&mut self, message: &str) {
+        let line = format!("[seed {} game {}] {}", self.seed, self.round, message);
+        match self.frontend {
+            Frontend::Window => println!("{line}"),
+            Frontend::Terminal => self.log_lines.push(line),
         }
-        let white_pins: i32 = color_occurences_both.iter().sum();
+    }
 
-        (black_pins, white_pins)
+    /// The log lines kept back since the last call.
+    pub fn take_log(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.log_lines)
     }
 
     fn check_send_guess(&self) -> bool {
         self.guess_input_field.get_send_guess()
     }
 
-    fn create_empty_guessed(number_of_guesses: usize) -> Vec<Vec<GuessField>> {
+    fn create_empty_guessed(code_length: usize, number_of_guesses: usize) -> Vec<Vec<GuessField>> {
         let mut guessed = vec![];
         let mut row = 1 + FIELD_SIZE + SPACING;
         for _ in 0..number_of_guesses {
-            guessed.push(vec![
-                GuessField {
-                    x: 1,
-                    y: row,
-                    color: Colors::Empty,
-                },
-                GuessField {
-                    x: 1 + FIELD_SIZE + SPACING,
-                    y: row,
-                    color: Colors::Empty,
-                },
-                GuessField {
-                    x: 1 + 2 * FIELD_SIZE + 2 * SPACING,
-                    y: row,
-                    color: Colors::Empty,
-                },
-                GuessField {
-                    x: 1 + 3 * FIELD_SIZE + 3 * SPACING,
-                    y: row,
-                    color: Colors::Empty,
-                },
-            ]);
+            guessed.push(
+                (0..code_length as i32)
+                    .map(|i| GuessField {
+                        x: 1 + i * FIELD_SIZE + i * SPACING,
+                        y: row,
+                        color: Colors::Empty,
+                    })
+                    .collect(),
+            );
             row = row + SPACING + FIELD_SIZE;
         }
-        return guessed;
+        guessed
     }
 
-    fn create_empty_guess_validation(number_of_guesses: usize) -> Vec<ValidationField> {
+    fn create_empty_guess_validation(
+        code_length: usize,
+        number_of_guesses: usize,
+    ) -> Vec<ValidationField> {
         let mut guess_validation = vec![];
         let mut row = 1 + FIELD_SIZE + SPACING;
-        let column = 1 + 4 * FIELD_SIZE + 4 * SPACING;
+        let column = 1 + (code_length as i32) * (FIELD_SIZE + SPACING);
         for _ in 0..number_of_guesses {
             guess_validation.push(ValidationField {
                 x: column,
                 y: row,
-                pins: vec![Colors::Empty; 4],
+                pins: vec![Colors::Empty; code_length],
+                feedback: Feedback::default(),
             });
             row = row + SPACING + FIELD_SIZE;
         }
-        return guess_validation;
+        guess_validation
+    }
+
+    fn create_new_secret(
+        mode: Mode,
+        rules: &Rules,
+        rng: &mut StdRng,
+        debug: bool,
+    ) -> Vec<SecretField> {
+        match mode {
+            // a player enters it first
+            Mode::Codemaker { .. } | Mode::HotSeat => Game::unknown_secret(rules.code_length),
+            Mode::Codebreaker => Game::secret_fields(&Code::random(rules, rng), debug),
+        }
     }
 
-    fn create_new_secret(debug: bool) -> Vec<SecretField> {
-        let color_options = Colors::create_color_list();
-        let mut rng = thread_rng();
+    /// Whether the secret is set: in codemaker and hot seat mode a player
+    /// enters it first, and the client of a race learns it when the race is
+    /// over.
+    fn secret_known(&self) -> bool {
+        self.secret.iter().all(|field| field.color != Colors::Empty)
+    }
+
+    fn unknown_secret(code_length: usize) -> Vec<SecretField> {
+        Game::secret_fields(&Code::new(vec![Colors::Empty; code_length]), false)
+    }
+
+    fn secret_fields(code: &Code, show_color: bool) -> Vec<SecretField> {
         let mut secret = vec![];
-        for i in 0..4 {
+        for (i, color) in code.pegs().iter().enumerate() {
+            let i = i as i32;
             secret.push(SecretField {
                 x: 1 + i * FIELD_SIZE + i * SPACING,
                 y: 1,
-                color: color_options[rng.gen_range(1..(color_options.len() - 1))],
-                show_color: debug,
+                color: *color,
+                show_color,
             });
         }
-        return secret;
-    }
-
-    fn calculate_guess_input_position(
-        width: i32,
-        height: i32,
-        number_of_guesses: i32,
-    ) -> (i32, i32) {
-        // 2 blocks for sides, for each guess field 2 blocks, for guess validation field 2 blocks = 12 + 4 * Spacing
-        let min_width = 12 + 4 * SPACING;
-        // 2 sides, secret, guesses, inputfield
-        let min_height = 2 + 2 + 2 * number_of_guesses + number_of_guesses * SPACING + SPACING + 2;
-        if min_width > width || min_height > height {
-            println!(
-                "Game configuration too small, min_width: {min_width}, min_height: {min_height}"
-            );
-            process::exit(0);
-        }
+        secret
+    }
+
+    /// Smallest window (in blocks) that fits the board for the given rules.
+    pub fn window_size(rules: &Rules, number_of_guesses: usize) -> (i32, i32) {
+        let code_length = rules.code_length as i32;
+        let number_of_guesses = number_of_guesses as i32;
+        // side, guess fields, validation pins (at least as wide as the enter block), side
+        let validation_width = i32::max(2, ValidationField::columns(rules.code_length) as i32);
+        let width = 1 + code_length * (FIELD_SIZE + SPACING) + validation_width + 1;
+        // 2 sides, secret, guesses, inputfield, palette, status lines
+        let height = 2
+            + 2
+            + 2 * number_of_guesses
+            + number_of_guesses * SPACING
+            + SPACING
+            + 2
+            + 1
+            + PALETTE_HEIGHT
+            + 1
+            + STATUS_LINES;
+        (i32::max(width, MIN_WIDTH), i32::max(height, MIN_HEIGHT))
+    }
+
+    fn calculate_guess_input_position(number_of_guesses: i32) -> (i32, i32) {
         let x = 1;
         let y = 1 + 2 + 2 * number_of_guesses + number_of_guesses * SPACING + SPACING;
-        return (x, y);
+        (x, y)
+    }
+}
+
+fn plural<'a>(count: usize, one: &'a str, many: &'a str) -> &'a str {
+    match count {
+        1 => one,
+        _ => many,
+    }
+}
+
+fn replay_status(replay: &Replay) -> String {
+    match replay.insight() {
+        Some(Ok(insight)) => format!(
+            "{} {} LEFT",
+            insight.candidates,
+            plural(insight.candidates, "SECRET", "SECRETS")
+        ),
+        Some(Err(SolverError::TooManyCodes)) => "TOO MANY CODES TO SOLVE".to_string(),
+        None if replay.at_end() && replay.cracked() => format!(
+            "CRACKED IN {} {}",
+            replay.guesses(),
+            plural(replay.guesses(), "GUESS", "GUESSES")
+        ),
+        None if replay.at_end() => "NOT CRACKED".to_string(),
+        None => format!("GUESS {} OF {}", replay.shown().len(), replay.guesses()),
+    }
+}
+
+fn replay_status_detail(replay: &Replay) -> String {
+    match replay.insight() {
+        Some(Ok(insight)) => match &insight.best_guess {
+            Some(guess) => format!("SOLVER WOULD PLAY {guess}"),
+            None => "NOTHING LEFT TO PLAY".to_string(),
+        },
+        _ => "LEFT/RIGHT: STEP, I: SOLVER".to_string(),
+    }
+}
+
+fn contradiction_status(rows: &[usize]) -> String {
+    match rows {
+        [row] => format!("CONTRADICTS GUESS {}", row + 1),
+        rows => format!("CONTRADICTS {} GUESSES", rows.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage;
+
+    #[test]
+    fn restoring_a_played_daily_records_nothing() {
+        storage::use_test_dir();
+        let options = Options {
+            daily: true,
+            ..Options::default()
+        };
+        let secret = Game::new(&options, None).secret_code();
+        Daily::today(options.rules).record(6, &[secret]).unwrap();
+
+        let recorded = history::load().unwrap().len();
+        let game = Game::new(&options, None);
+        assert!(game.game_won);
+        assert_eq!(history::load().unwrap().len(), recorded);
+    }
+
+    /// A codebreaker game against `secret` with `guesses` played. They are
+    /// played as if restored, so a game they end isn't recorded.
+    fn game_against(secret: &str, guesses: &[&str]) -> Game {
+        storage::use_test_dir();
+        let mut game = Game::new(&Options::default(), None);
+        game.secret = Game::secret_fields(&secret.parse().unwrap(), false);
+        game.restoring = true;
+        for guess in guesses {
+            play(&mut game, guess);
+        }
+        game
+    }
+
+    fn play(game: &mut Game, guess: &str) {
+        let guess = guess.parse().unwrap();
+        let feedback = game.check_guess_against_secret(&guess);
+        game.set_validation_pins_and_save_guess(feedback, &guess);
+    }
+
+    #[test]
+    fn finds_the_rows_a_guess_contradicts() {
+        let game = game_against("RGBY", &["RRGG", "BBYY"]);
+        let contradicted = |guess: &str| game.contradicted_rows(&guess.parse().unwrap());
+        assert_eq!(contradicted("RGBY"), Vec::<usize>::new());
+        assert_eq!(contradicted("RRGG"), [0, 1]);
+        assert_eq!(contradicted("BBYY"), [0, 1]);
+        assert_eq!(contradicted("RGKY"), [1]);
+    }
+
+    #[test]
+    fn codemaker_scores_the_guesses_and_hints_needed() {
+        let mut game = game_against("RGBY", &["RRGG", "BBYY", "RGBY"]);
+        assert!(game.game_won);
+        assert_eq!(game.codemaker_points(), 3);
+        game.hints_used = 2;
+        assert_eq!(game.codemaker_points(), 5);
+
+        // a code that wasn't cracked scores one more
+        let game = game_against("RGBY", &["RRGG"; 6]);
+        assert!(game.game_over && !game.game_won);
+        assert_eq!(game.codemaker_points(), 7);
+    }
+
+    #[test]
+    fn client_keeps_the_secret_hidden_when_the_host_leaves() {
+        storage::use_test_dir();
+        let options = Options {
+            network: Some(Network::Connect(String::new())),
+            ..Options::default()
+        };
+        let mut game = Game::new(&options, None);
+        let recorded = history::load().unwrap().len();
+        game.lose_connection(io::ErrorKind::UnexpectedEof.into());
+
+        assert_eq!(game.race, Some(Outcome::Won));
+        assert!(game.game_over);
+        assert!(game.view().secret.iter().all(|peg| *peg == Colors::Secret));
+        assert_eq!(history::load().unwrap().len(), recorded);
+    }
+
+    #[test]
+    fn a_save_follows_the_game_until_it_ends() {
+        let mut game = game_against("RGBY", &["RRGG"]);
+        game.key_pressed(Key::S);
+        assert_eq!(SavedGame::load().unwrap().rows().unwrap().len(), 1);
+        play(&mut game, "BBYY");
+        assert_eq!(SavedGame::load().unwrap().rows().unwrap().len(), 2);
+        play(&mut game, "RGBY");
+        assert!(SavedGame::load().is_err());
     }
 }
@@ -1,31 +1,28 @@
+use mastermind::{Code, Colors, Rules};
 use piston_window::{types::Color, Context, G2d, Key};
-use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
 
 use crate::{
-    draw::{draw_big_block, draw_rectangle},
-    COLOR_BLACK, COLOR_BLUE, COLOR_EMPTY, COLOR_GREEN, COLOR_RED, COLOR_SECRET, COLOR_WHITE,
-    COLOR_YELLOW, FIELD_SIZE, SPACING,
+    draw::{draw_big_block, draw_big_hole, draw_rectangle, draw_text},
+    COLOR_BLACK, COLOR_BLANK, COLOR_BLUE, COLOR_BROWN, COLOR_CYAN, COLOR_EMPTY, COLOR_GREEN,
+    COLOR_HOLE, COLOR_ORANGE, COLOR_PINK, COLOR_PURPLE, COLOR_RED, COLOR_SECRET, COLOR_TEXT,
+    COLOR_WHITE, COLOR_YELLOW, FIELD_SIZE, SPACING,
 };
 
 const COLOR_CURRENT_POSITION: Color = [0.2, 0.2, 0.2, 0.5];
-const SIZE: i32 = 4;
 const FLASH_TIMER: i32 = 4;
+const REJECTED_TIMER: i32 = 10;
+// the palette below the input field, a row of swatches (one per playable
+// color) and a row with the key of each swatch
+pub const PALETTE_HEIGHT: i32 = 2;
+const PALETTE_SPACING: i32 = 1;
 
-#[derive(PartialEq, EnumIter, Debug, Copy, Clone)]
-pub enum Colors {
-    Empty,
-    Red,
-    Blue,
-    Green,
-    Yellow,
-    Black,
-    White,
-    Secret,
+/// Maps the colors of the rules onto the colors drawn by piston.
+pub trait DrawColor {
+    fn return_color(&self) -> Color;
 }
 
-impl Colors {
-    pub fn return_color(&self) -> Color {
+impl DrawColor for Colors {
+    fn return_color(&self) -> Color {
         match self {
             Colors::Empty => COLOR_EMPTY,
             Colors::Red => COLOR_RED,
@@ -34,24 +31,61 @@ impl Colors {
             Colors::Yellow => COLOR_YELLOW,
             Colors::Black => COLOR_BLACK,
             Colors::White => COLOR_WHITE,
+            Colors::Orange => COLOR_ORANGE,
+            Colors::Purple => COLOR_PURPLE,
+            Colors::Cyan => COLOR_CYAN,
+            Colors::Brown => COLOR_BROWN,
+            Colors::Pink => COLOR_PINK,
+            Colors::Blank => COLOR_BLANK,
             Colors::Secret => COLOR_SECRET,
         }
     }
+}
 
-    pub fn create_color_list() -> Vec<Colors> {
-        let mut color_options = vec![];
-        for color in Colors::iter() {
-            if color != Colors::Secret {
-                color_options.push(color);
-            }
-        }
-        return color_options;
+/// Draws a peg as a big block, a blank peg is drawn as an open hole so it
+/// can't be mistaken for an empty slot.
+pub fn draw_peg(color: Colors, x: i32, y: i32, con: &Context, g: &mut G2d) {
+    if color == Colors::Blank {
+        draw_big_hole(color.return_color(), COLOR_HOLE, x, y, con, g);
+    } else {
+        draw_big_block(color.return_color(), x, y, con, g);
     }
 }
 
+// 1 to 9 pick the first nine palette colors, 0 the tenth and the keys next
+// to it the last two, so even 11 colors with blanks can all be picked
+const COLOR_KEYS: [(Key, Key); 12] = [
+    (Key::D1, Key::NumPad1),
+    (Key::D2, Key::NumPad2),
+    (Key::D3, Key::NumPad3),
+    (Key::D4, Key::NumPad4),
+    (Key::D5, Key::NumPad5),
+    (Key::D6, Key::NumPad6),
+    (Key::D7, Key::NumPad7),
+    (Key::D8, Key::NumPad8),
+    (Key::D9, Key::NumPad9),
+    (Key::D0, Key::NumPad0),
+    (Key::Minus, Key::NumPadMinus),
+    (Key::Equals, Key::NumPadPlus),
+];
+const COLOR_KEY_LABELS: &str = "1234567890-=";
+
+/// The palette index a color key selects.
+fn color_key(key: Key) -> Option<usize> {
+    COLOR_KEYS
+        .iter()
+        .position(|(key_row, num_pad)| key == *key_row || key == *num_pad)
+}
+
+/// The key that picks the palette color at `index`, as shown below it.
+pub fn color_key_label(index: usize) -> Option<char> {
+    COLOR_KEY_LABELS.chars().nth(index)
+}
+
 pub struct GuessInputField {
     fields: Vec<Colors>,
     color_options: Vec<Colors>,
+    rules: Rules,
     ready: bool,
     send_guess: bool,
     current_position: usize,
@@ -59,32 +93,39 @@ pub struct GuessInputField {
 
     flashing_visible: bool,
     flash_timer: i32,
+    // counts down while the last guess is shown as rejected
+    rejected_timer: i32,
+
+    // earlier and undone states of the fields
+    undo_history: Vec<Vec<Colors>>,
+    redo_history: Vec<Vec<Colors>>,
 
     gui_position_x: i32,
     gui_position_y: i32,
 }
 
 impl GuessInputField {
-    pub fn new(gui_position_x: i32, gui_position_y: i32) -> GuessInputField {
-        let mut fields = vec![];
-        for _ in 0..SIZE {
-            fields.push(Colors::Empty);
-        }
+    pub fn new(gui_position_x: i32, gui_position_y: i32, rules: &Rules) -> GuessInputField {
+        let fields = vec![Colors::Empty; rules.code_length];
 
-        let color_options = Colors::create_color_list();
+        let color_options = rules.color_options();
 
-        return GuessInputField {
+        GuessInputField {
             fields,
             color_options,
+            rules: *rules,
             ready: false,
             send_guess: false,
             current_position: 0,
             disabled: false,
             flashing_visible: true,
             flash_timer: FLASH_TIMER,
+            rejected_timer: 0,
+            undo_history: vec![],
+            redo_history: vec![],
             gui_position_x,
             gui_position_y,
-        };
+        }
     }
 
     pub fn get_send_guess(&self) -> bool {
@@ -95,11 +136,39 @@ impl GuessInputField {
         &self.fields
     }
 
+    /// The selected field, the length of the guess for the enter block.
+    pub fn position(&self) -> usize {
+        self.current_position
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+
     pub fn reset_guess(&mut self) {
-        self.fields = vec![Colors::Empty; 4];
+        self.fields = vec![Colors::Empty; self.fields.len()];
         self.ready = false;
         self.send_guess = false;
         self.current_position = 0;
+        self.rejected_timer = 0;
+        self.undo_history.clear();
+        self.redo_history.clear();
+    }
+
+    /// Replaces the fields with `guess` as a starting point for the next one.
+    pub fn set_guess(&mut self, guess: &[Colors]) {
+        if self.disabled {
+            return;
+        }
+        let before = self.fields.clone();
+        self.fields = guess.to_vec();
+        self.current_position = 0;
+        self.record_edit(before);
+    }
+
+    /// Takes a submit back, the guess stays in the fields.
+    pub fn hold_guess(&mut self) {
+        self.send_guess = false;
     }
 
     pub fn disable_input(&mut self) {
@@ -110,15 +179,77 @@ impl GuessInputField {
         self.disabled = false;
     }
 
+    /// Handles a click on the block `x`, `y`: selects a field, submits on the
+    /// enter block or colors the current field from the palette.
+    pub fn mouse_pressed(&mut self, x: i32, y: i32) {
+        if self.disabled {
+            return;
+        }
+        let before = self.fields.clone();
+        let row_x = x - self.gui_position_x;
+        let column = row_x / (FIELD_SIZE + SPACING);
+        let in_column = row_x >= 0 && row_x % (FIELD_SIZE + SPACING) < FIELD_SIZE;
+        if in_column
+            && (column as usize) < self.fields.len()
+            && (self.gui_position_y..self.gui_position_y + FIELD_SIZE).contains(&y)
+        {
+            self.current_position = column as usize;
+        }
+        if in_column
+            && column as usize == self.ready_position()
+            && (self.gui_position_y - 1..self.gui_position_y + FIELD_SIZE).contains(&y)
+        {
+            self.current_position = self.ready_position();
+            self.send_guess();
+        }
+
+        let palette_y = self.palette_y();
+        if row_x >= 0
+            && row_x % (1 + PALETTE_SPACING) == 0
+            && (palette_y..palette_y + PALETTE_HEIGHT).contains(&y)
+        {
+            if let Some(color) = self.palette().get((row_x / (1 + PALETTE_SPACING)) as usize) {
+                self.set_color(*color);
+            }
+        }
+        self.record_edit(before);
+    }
+
+    /// The first row below the input field and its palette.
+    pub fn bottom(&self) -> i32 {
+        self.palette_y() + PALETTE_HEIGHT
+    }
+
     pub fn key_pressed(&mut self, key: Key) {
-        if !self.disabled {
-            match key {
-                Key::Left => self.move_current_position(-1), // move current position left
-                Key::Right => self.move_current_position(1), // move current position right
-                Key::Up => self.change_color(1),             // choose Color
-                Key::Down => self.change_color(-1),          // choose Color
-                Key::Return => self.send_guess(),            // if on ready block set ready
-                _ => {}
+        if self.disabled {
+            return;
+        }
+        match key {
+            Key::Z => self.undo(), // undo the last edit
+            Key::Y => self.redo(), // redo the last undone edit
+            key => {
+                let before = self.fields.clone();
+                self.edit_key_pressed(key);
+                self.record_edit(before);
+            }
+        }
+    }
+
+    fn edit_key_pressed(&mut self, key: Key) {
+        match key {
+            Key::Left => self.move_current_position(-1), // move current position left
+            Key::Right => self.move_current_position(1), // move current position right
+            Key::Up => self.change_color(1),             // choose Color
+            Key::Down => self.change_color(-1),          // choose Color
+            Key::Return => self.send_guess(),            // if on ready block set ready
+            Key::Backspace => self.clear_backwards(),    // clear current or previous peg
+            Key::Delete => self.clear_current(),         // clear the current peg
+            Key::C => self.clear_all(),                  // clear the whole row
+            key => {
+                // the color keys pick a palette color directly
+                if let Some(color) = color_key(key).and_then(|i| self.palette().get(i)) {
+                    self.set_color(*color);
+                }
             }
         }
     }
@@ -126,8 +257,8 @@ impl GuessInputField {
     pub fn draw(&self, con: &Context, g: &mut G2d) {
         let mut i = 0;
         for block in &self.fields {
-            draw_big_block(
-                block.return_color(),
+            draw_peg(
+                *block,
                 self.gui_position_x + i * SPACING + i * FIELD_SIZE,
                 self.gui_position_y,
                 con,
@@ -135,6 +266,38 @@ impl GuessInputField {
             );
             i += 1;
         }
+        // mark repeated colors of a rejected guess
+        if self.rejected_timer > 0 {
+            for (j, block) in self.fields.iter().enumerate() {
+                if self.fields.iter().filter(|other| *other == block).count() > 1 {
+                    let j = j as i32;
+                    draw_rectangle(
+                        COLOR_RED,
+                        self.gui_position_x + j * SPACING + j * FIELD_SIZE,
+                        self.gui_position_y + FIELD_SIZE,
+                        FIELD_SIZE,
+                        1,
+                        con,
+                        g,
+                    );
+                }
+            }
+        }
+        // draw palette
+        for (j, color) in self.palette().iter().enumerate() {
+            let x = self.gui_position_x + j as i32 * (1 + PALETTE_SPACING);
+            draw_rectangle(color.return_color(), x, self.palette_y(), 1, 1, con, g);
+            if let Some(label) = color_key_label(j) {
+                draw_text(
+                    COLOR_TEXT,
+                    &label.to_string(),
+                    x,
+                    self.palette_y() + 1,
+                    con,
+                    g,
+                );
+            }
+        }
         // draw enterBlock
         let color = match self.ready {
             true => COLOR_GREEN,
@@ -151,7 +314,7 @@ impl GuessInputField {
         );
         // draw current position
         if self.flashing_visible {
-            if self.current_position == 4 {
+            if self.current_position == self.ready_position() {
                 draw_rectangle(
                     COLOR_CURRENT_POSITION,
                     self.gui_position_x + i * SPACING + i * FIELD_SIZE,
@@ -176,38 +339,43 @@ impl GuessInputField {
     }
 
     pub fn update(&mut self) {
-        if !self.fields.contains(&Colors::Empty) {
-            self.ready = true;
-        } else {
-            self.ready = false;
-        }
+        self.ready = !self.fields.contains(&Colors::Empty);
         // allow to flash
         if self.flash_timer <= 0 {
             self.flashing_visible = !self.flashing_visible;
             self.flash_timer = FLASH_TIMER;
         }
         self.flash_timer -= 1;
+        if self.rejected_timer > 0 {
+            self.rejected_timer -= 1;
+        }
     }
 
     fn send_guess(&mut self) {
-        if self.current_position == 4 && self.ready {
-            self.send_guess = true
+        if self.current_position == self.ready_position() && self.ready {
+            if self
+                .rules
+                .check_guess(&Code::new(self.fields.clone()))
+                .is_ok()
+            {
+                self.send_guess = true
+            } else {
+                self.rejected_timer = REJECTED_TIMER;
+            }
         }
     }
 
     fn move_current_position(&mut self, dir: i32) {
         if dir == -1 && self.current_position > 0 {
             self.current_position -= 1
-        } else if dir == 1 && self.current_position < 4 {
+        } else if dir == 1 && self.current_position < self.ready_position() {
             self.current_position += 1
-        } else {
-            return;
         }
     }
 
     fn change_color(&mut self, dir: i32) {
-        // check we are not at ready position (= 4)
-        if self.current_position == 4 {
+        // check we are not at ready position
+        if self.current_position == self.ready_position() {
             return;
         }
         let mut index = self
@@ -222,4 +390,148 @@ impl GuessInputField {
         }
         self.fields[self.current_position] = self.color_options[index];
     }
+
+    /// Empties the current field, or if it is empty already the one before
+    /// it and moves back onto that one.
+    fn clear_backwards(&mut self) {
+        // on the enter block or an empty field go back first
+        let current = self.fields.get(self.current_position);
+        if current.is_none_or(|field| *field == Colors::Empty) {
+            if self.current_position == 0 {
+                return;
+            }
+            self.current_position -= 1;
+        }
+        self.clear_current();
+    }
+
+    fn clear_current(&mut self) {
+        if let Some(field) = self.fields.get_mut(self.current_position) {
+            *field = Colors::Empty;
+        }
+    }
+
+    fn clear_all(&mut self) {
+        self.fields = vec![Colors::Empty; self.fields.len()];
+        self.current_position = 0;
+    }
+
+    /// Remembers the fields as they were before an edit, if it changed them.
+    fn record_edit(&mut self, before: Vec<Colors>) {
+        if before != self.fields {
+            self.undo_history.push(before);
+            self.redo_history.clear();
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(fields) = self.undo_history.pop() {
+            self.redo_history
+                .push(std::mem::replace(&mut self.fields, fields));
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(fields) = self.redo_history.pop() {
+            self.undo_history
+                .push(std::mem::replace(&mut self.fields, fields));
+        }
+    }
+
+    /// Colors the current field and moves on to the next one.
+    fn set_color(&mut self, color: Colors) {
+        if self.current_position == self.ready_position() {
+            return;
+        }
+        self.fields[self.current_position] = color;
+        self.move_current_position(1);
+    }
+
+    /// The colors that can be picked, in the order of the color keys.
+    pub fn palette(&self) -> &[Colors] {
+        &self.color_options[1..]
+    }
+
+    fn palette_y(&self) -> i32 {
+        // leave a row for the marks of a rejected guess
+        self.gui_position_y + FIELD_SIZE + 1
+    }
+
+    /// The enter block sits right after the last field.
+    fn ready_position(&self) -> usize {
+        self.fields.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Colors::*;
+
+    fn field(keys: &[Key]) -> GuessInputField {
+        let mut field = GuessInputField::new(0, 0, &Rules::default());
+        for key in keys {
+            field.key_pressed(*key);
+        }
+        field
+    }
+
+    #[test]
+    fn undo_and_redo_step_through_the_edits() {
+        let mut field = field(&[Key::D1, Key::D2, Key::D3]);
+        assert_eq!(field.get_guess(), &[Red, Blue, Green, Empty]);
+
+        field.key_pressed(Key::Z);
+        assert_eq!(field.get_guess(), &[Red, Blue, Empty, Empty]);
+        field.key_pressed(Key::Z);
+        assert_eq!(field.get_guess(), &[Red, Empty, Empty, Empty]);
+        field.key_pressed(Key::Y);
+        assert_eq!(field.get_guess(), &[Red, Blue, Empty, Empty]);
+
+        // a new edit drops what was undone
+        field.key_pressed(Key::C);
+        assert_eq!(field.get_guess(), &[Empty; 4]);
+        field.key_pressed(Key::Y);
+        assert_eq!(field.get_guess(), &[Empty; 4]);
+        field.key_pressed(Key::Z);
+        assert_eq!(field.get_guess(), &[Red, Blue, Empty, Empty]);
+    }
+
+    #[test]
+    fn moves_that_change_nothing_are_not_undone() {
+        let mut field = field(&[Key::D1, Key::Left, Key::Right, Key::Right]);
+        field.key_pressed(Key::Z);
+        assert_eq!(field.get_guess(), &[Empty; 4]);
+        // nothing left to undo
+        field.key_pressed(Key::Z);
+        assert_eq!(field.get_guess(), &[Empty; 4]);
+    }
+
+    #[test]
+    fn backspace_clears_the_peg_or_goes_back_first() {
+        let mut field = field(&[Key::D1, Key::D2, Key::D3, Key::Left]);
+        assert_eq!(field.position(), 2);
+        // the current peg is cleared and the position stays
+        field.key_pressed(Key::Backspace);
+        assert_eq!(field.get_guess(), &[Red, Blue, Empty, Empty]);
+        assert_eq!(field.position(), 2);
+        // on an empty peg the one before is cleared
+        field.key_pressed(Key::Backspace);
+        assert_eq!(field.get_guess(), &[Red, Empty, Empty, Empty]);
+        assert_eq!(field.position(), 1);
+        field.key_pressed(Key::Backspace);
+        field.key_pressed(Key::Backspace);
+        assert_eq!(field.get_guess(), &[Empty; 4]);
+        assert_eq!(field.position(), 0);
+    }
+
+    #[test]
+    fn delete_only_clears_the_current_peg() {
+        let mut field = field(&[Key::D1, Key::D2, Key::D3, Key::D4, Key::Left, Key::Left]);
+        field.key_pressed(Key::Delete);
+        assert_eq!(field.get_guess(), &[Red, Blue, Empty, Yellow]);
+        field.key_pressed(Key::Delete);
+        assert_eq!(field.get_guess(), &[Red, Blue, Empty, Yellow]);
+        assert_eq!(field.position(), 2);
+    }
 }
@@ -0,0 +1,80 @@
+use mastermind::Rules;
+use piston_window::{Context, G2d};
+
+use crate::{
+    draw::{draw_rectangle, draw_text},
+    COLOR_OVERLAY, COLOR_TEXT,
+};
+
+const CONTROLS: [&str; 20] = [
+    "LEFT/RIGHT   SELECT A PEG",
+    "UP/DOWN      CHANGE COLOR",
+    "1-9,0,-,=    SET COLOR, NEXT",
+    "BACKSPACE    CLEAR PEG, BACK",
+    "DELETE/C     CLEAR PEG/ROW",
+    "Z/Y          UNDO/REDO",
+    "P            COPY AN EARLIER",
+    "             GUESS",
+    "RETURN       SUBMIT (ON THE",
+    "             GREEN BLOCK)",
+    "MOUSE        CLICK A PEG, A",
+    "             COLOR OR SUBMIT",
+    "I            HINT",
+    "B/W          ADD A PIN",
+    "S            SAVE, --resume",
+    "T            STATISTICS",
+    "R            RESTART",
+    "H            HELP",
+    "ESC          QUIT",
+    "",
+];
+
+const PINS: [&str; 5] = [
+    "BLACK  RIGHT COLOR IN THE",
+    "       RIGHT POSITION",
+    "WHITE  RIGHT COLOR IN THE",
+    "       WRONG POSITION",
+    "",
+];
+
+/// The lines of the help screen, `game_name` tells which game is played.
+pub fn lines(rules: &Rules, number_of_guesses: usize, game_name: &str) -> Vec<String> {
+    let mut lines = vec!["HELP".to_string(), "".to_string(), "CONTROLS".to_string()];
+    lines.extend(CONTROLS.iter().map(|line| line.to_string()));
+    lines.push("PINS".to_string());
+    lines.extend(PINS.iter().map(|line| line.to_string()));
+
+    lines.push("RULES".to_string());
+    lines.push(format!(
+        "{} PEGS, {} COLORS",
+        rules.code_length, rules.number_of_colors
+    ));
+    lines.push(format!("{number_of_guesses} GUESSES"));
+    lines.push(
+        match (rules.allow_duplicates, rules.reject_duplicate_guesses) {
+            (true, _) => "DUPLICATES ALLOWED",
+            (false, false) => "NO DUPLICATES IN SECRET",
+            (false, true) => "NO DUPLICATES AT ALL",
+        }
+        .to_string(),
+    );
+    lines.push(
+        match rules.allow_blanks {
+            true => "BLANKS ALLOWED",
+            false => "NO BLANKS",
+        }
+        .to_string(),
+    );
+    lines.push(game_name.to_uppercase());
+    lines.push("".to_string());
+    lines.push("PRESS ANY KEY TO CLOSE".to_string());
+    lines
+}
+
+/// Draws the help or another screen of text over the whole board.
+pub fn draw(lines: &[String], width: i32, height: i32, con: &Context, g: &mut G2d) {
+    draw_rectangle(COLOR_OVERLAY, 0, 0, width, height, con, g);
+    for (row, line) in lines.iter().enumerate() {
+        draw_text(COLOR_TEXT, line, 1, 1 + row as i32, con, g);
+    }
+}
@@ -0,0 +1,405 @@
+//! Every finished game, one JSON object per line in `history.jsonl` in the
+//! data directory:
+//!
+//! ```json
+//! {"time":"2026-10-18T20:15:03+02:00","mode":"codebreaker","pegs":4,"colors":6,
+//!  "duplicates":true,"unique_guesses":false,"blanks":false,"guesses":6,
+//!  "secret":"GRRY","rows":[{"guess":"RRBB","black":1,"white":1}],"hints":0,
+//!  "cracked":true,"seconds":84}
+//! ```
+//!
+//! (on one line in the file). Races also record `"race": "won"`, `"lost"` or
+//! `"draw"`.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+};
+
+use chrono::{Local, SecondsFormat};
+use mastermind::{Code, Feedback, ParseCodeError, Rules};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    net::Outcome,
+    options::{MAX_GUESSES, MIN_GUESSES},
+    storage,
+};
+
+const HISTORY_FILE: &str = "history.jsonl";
+// the longest bar of the histogram
+const BAR_WIDTH: usize = 16;
+
+/// Which game was played, seen from the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Played {
+    Codebreaker,
+    Daily,
+    Race,
+    Codemaker,
+    HotSeat,
+}
+
+/// A guess with its feedback, as the save and history files store it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Row {
+    guess: String,
+    black: usize,
+    white: usize,
+}
+
+impl Row {
+    pub fn new(guess: &Code, feedback: Feedback) -> Row {
+        Row {
+            guess: guess.to_string(),
+            black: feedback.black,
+            white: feedback.white,
+        }
+    }
+
+    pub fn guess(&self) -> Result<Code, String> {
+        self.guess
+            .parse()
+            .map_err(|error: ParseCodeError| error.to_string())
+    }
+
+    pub fn feedback(&self) -> Feedback {
+        Feedback {
+            black: self.black,
+            white: self.white,
+        }
+    }
+}
+
+/// The rules and the number of guesses of a game, as the save and history
+/// files store them.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Setup {
+    pegs: usize,
+    colors: usize,
+    duplicates: bool,
+    unique_guesses: bool,
+    blanks: bool,
+    guesses: usize,
+}
+
+impl Setup {
+    pub fn new(rules: &Rules, number_of_guesses: usize) -> Setup {
+        Setup {
+            pegs: rules.code_length,
+            colors: rules.number_of_colors,
+            duplicates: rules.allow_duplicates,
+            unique_guesses: rules.reject_duplicate_guesses,
+            blanks: rules.allow_blanks,
+            guesses: number_of_guesses,
+        }
+    }
+
+    pub fn rules(&self) -> Rules {
+        Rules {
+            code_length: self.pegs,
+            number_of_colors: self.colors,
+            allow_duplicates: self.duplicates,
+            reject_duplicate_guesses: self.unique_guesses,
+            allow_blanks: self.blanks,
+        }
+    }
+
+    pub fn number_of_guesses(&self) -> usize {
+        self.guesses
+    }
+
+    /// Checks what was read from a file, the options can't have been
+    /// checked for it.
+    pub fn validate(&self) -> Result<(), String> {
+        self.rules().validate().map_err(|error| error.to_string())?;
+        if !(MIN_GUESSES..=MAX_GUESSES).contains(&self.guesses) {
+            return Err(format!(
+                "{} guesses, not {MIN_GUESSES} to {MAX_GUESSES}",
+                self.guesses
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    time: String,
+    mode: Played,
+    #[serde(flatten)]
+    setup: Setup,
+    secret: String,
+    rows: Vec<Row>,
+    hints: usize,
+    /// Whether the secret was cracked within the guesses.
+    cracked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    race: Option<Outcome>,
+    seconds: u64,
+}
+
+impl Record {
+    /// A game that just finished.
+    pub fn new(
+        mode: Played,
+        setup: Setup,
+        secret: &Code,
+        rows: &[(Code, Feedback)],
+        hints: usize,
+        race: Option<Outcome>,
+        seconds: u64,
+    ) -> Record {
+        Record {
+            time: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+            mode,
+            setup,
+            secret: secret.to_string(),
+            rows: rows
+                .iter()
+                .map(|(guess, feedback)| Row::new(guess, *feedback))
+                .collect(),
+            hints,
+            cracked: rows
+                .last()
+                .is_some_and(|(_, feedback)| feedback.black == setup.pegs),
+            race,
+            seconds,
+        }
+    }
+
+    pub fn time(&self) -> &str {
+        &self.time
+    }
+
+    pub fn setup(&self) -> Setup {
+        self.setup
+    }
+
+    pub fn secret(&self) -> Result<Code, String> {
+        self.secret
+            .parse()
+            .map_err(|error: ParseCodeError| error.to_string())
+    }
+
+    pub fn rows(&self) -> Result<Vec<(Code, Feedback)>, String> {
+        self.rows
+            .iter()
+            .map(|row| Ok((row.guess()?, row.feedback())))
+            .collect()
+    }
+
+    /// Whether the player cracked the secret, the statistics are about
+    /// these games.
+    fn player_breaks(&self) -> bool {
+        matches!(
+            self.mode,
+            Played::Codebreaker | Played::Daily | Played::Race
+        )
+    }
+
+    /// Statistics are kept per rules, except for rejecting duplicate
+    /// guesses which doesn't change the puzzle.
+    fn same_puzzle(&self, rules: &Rules) -> bool {
+        let setup = &self.setup;
+        setup.pegs == rules.code_length
+            && setup.colors == rules.number_of_colors
+            && setup.duplicates == rules.allow_duplicates
+            && setup.blanks == rules.allow_blanks
+    }
+}
+
+/// Appends a finished game to the history file.
+pub fn append(record: &Record) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(storage::data_file(HISTORY_FILE)?)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)
+}
+
+/// All recorded games, oldest first. Lines that can't be read are skipped.
+pub fn load() -> io::Result<Vec<Record>> {
+    let content = match fs::read_to_string(storage::data_file(HISTORY_FILE)?) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(error),
+    };
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// How the player did so far in the games with the same rules.
+#[derive(Debug, Default)]
+pub struct Statistics {
+    played: usize,
+    won: usize,
+    /// How many games were won with one guess, two guesses and so on.
+    wins_by_guesses: Vec<usize>,
+    current_streak: usize,
+    best_streak: usize,
+}
+
+impl Statistics {
+    pub fn of(records: &[Record], rules: &Rules) -> Statistics {
+        let mut statistics = Statistics::default();
+        let games = records
+            .iter()
+            .filter(|record| record.player_breaks() && record.same_puzzle(rules));
+        for record in games {
+            statistics.played += 1;
+            if !record.cracked {
+                statistics.current_streak = 0;
+                continue;
+            }
+            statistics.won += 1;
+            statistics.current_streak += 1;
+            statistics.best_streak = statistics.best_streak.max(statistics.current_streak);
+            let guesses = record.rows.len();
+            if statistics.wins_by_guesses.len() < guesses {
+                statistics.wins_by_guesses.resize(guesses, 0);
+            }
+            statistics.wins_by_guesses[guesses - 1] += 1;
+        }
+        statistics
+    }
+
+    fn average_guesses(&self) -> Option<f64> {
+        let guesses: usize = (1..)
+            .zip(&self.wins_by_guesses)
+            .map(|(guesses, wins)| guesses * wins)
+            .sum();
+        (self.won > 0).then(|| guesses as f64 / self.won as f64)
+    }
+
+    /// The lines of the statistics screen, with a histogram of the wins
+    /// for up to `number_of_guesses` guesses or more if there were longer
+    /// games.
+    pub fn lines(&self, rules: &Rules, number_of_guesses: usize) -> Vec<String> {
+        let mut lines = vec![
+            "STATISTICS".to_string(),
+            format!(
+                "{} PEGS, {} COLORS",
+                rules.code_length, rules.number_of_colors
+            ),
+            "".to_string(),
+            format!("PLAYED       {}", self.played),
+        ];
+        if let Some(rate) = (self.won * 100).checked_div(self.played) {
+            lines.push(format!("WON          {rate}%"));
+        }
+        if let Some(average) = self.average_guesses() {
+            lines.push(format!("AVERAGE      {average:.1} GUESSES"));
+        }
+        lines.push(format!("STREAK       {}", self.current_streak));
+        lines.push(format!("BEST STREAK  {}", self.best_streak));
+        lines.push("".to_string());
+
+        lines.push("GUESSES TO WIN".to_string());
+        let most = self.wins_by_guesses.iter().copied().max().unwrap_or(0);
+        let rows = number_of_guesses.max(self.wins_by_guesses.len());
+        for guesses in 1..=rows {
+            let wins = self.wins_by_guesses.get(guesses - 1).copied().unwrap_or(0);
+            // every win shows, however many the longest bar stands for
+            let bar = match wins {
+                0 => 0,
+                _ => (wins * BAR_WIDTH / most).max(1),
+            };
+            lines.push(format!("{guesses:>2} {} {wins}", "#".repeat(bar)));
+        }
+        lines.push("".to_string());
+        lines.push("PRESS ANY KEY TO CLOSE".to_string());
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mastermind::Colors;
+
+    /// A game that took `guesses` guesses and was won with the last one if
+    /// `cracked`.
+    fn game(mode: Played, rules: &Rules, guesses: usize, cracked: bool) -> Record {
+        let secret = Code::from(vec![Colors::Red; rules.code_length]);
+        let miss = Code::from(vec![Colors::Blue; rules.code_length]);
+        let mut rows = vec![(miss, Feedback::default()); guesses];
+        if cracked {
+            let black = rules.code_length;
+            rows[guesses - 1] = (secret.clone(), Feedback { black, white: 0 });
+        }
+        Record::new(mode, Setup::new(rules, 6), &secret, &rows, 0, None, 60)
+    }
+
+    #[test]
+    fn statistics_count_the_games_of_the_same_puzzle() {
+        let rules = Rules::default();
+        let other = Rules {
+            code_length: 5,
+            ..rules
+        };
+        let records = [
+            game(Played::Codebreaker, &rules, 3, true),
+            game(Played::Codebreaker, &other, 1, true),
+            game(Played::Race, &rules, 2, true),
+            game(Played::Codebreaker, &rules, 6, false),
+            game(Played::Codemaker, &rules, 5, true),
+            game(Played::Daily, &rules, 4, true),
+        ];
+        let statistics = Statistics::of(&records, &rules);
+        assert_eq!(
+            statistics.lines(&rules, 6),
+            [
+                "STATISTICS",
+                "4 PEGS, 6 COLORS",
+                "",
+                "PLAYED       4",
+                "WON          75%",
+                "AVERAGE      3.0 GUESSES",
+                "STREAK       1",
+                "BEST STREAK  2",
+                "",
+                "GUESSES TO WIN",
+                " 1  0",
+                " 2 ################ 1",
+                " 3 ################ 1",
+                " 4 ################ 1",
+                " 5  0",
+                " 6  0",
+                "",
+                "PRESS ANY KEY TO CLOSE",
+            ]
+        );
+
+        let statistics = Statistics::of(&records, &other);
+        assert_eq!(statistics.played, 1);
+        assert_eq!(statistics.wins_by_guesses, [1]);
+    }
+
+    #[test]
+    fn statistics_without_games_have_no_rates() {
+        let rules = Rules::default();
+        let lines = Statistics::of(&[], &rules).lines(&rules, 2);
+        assert!(lines.contains(&"PLAYED       0".to_string()));
+        assert!(!lines.iter().any(|line| line.starts_with("WON")));
+        assert!(!lines.iter().any(|line| line.starts_with("AVERAGE")));
+        assert!(lines.contains(&" 2  0".to_string()));
+    }
+
+    #[test]
+    fn records_keep_the_rules_at_the_top_level() {
+        let rules = Rules {
+            code_length: 5,
+            ..Rules::default()
+        };
+        let json = serde_json::to_string(&game(Played::Daily, &rules, 2, true)).unwrap();
+        assert!(json.contains(r#""mode":"daily","pegs":5,"colors":6,"duplicates":true,"unique_guesses":false,"blanks":false,"guesses":6,"secret":"RRRRR""#));
+        let record: Record = serde_json::from_str(&json).unwrap();
+        assert_eq!(record.setup().rules(), rules);
+        assert_eq!(record.setup().number_of_guesses(), 6);
+        assert!(record.cracked);
+    }
+}
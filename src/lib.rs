@@ -0,0 +1,14 @@
+//! Rules of mastermind without any frontend attached.
+//!
+//! The piston window in `main.rs` is only one user of this crate, solvers and
+//! bots can link against the rules with `default-features = false` and never
+//! pull in piston_window.
+
+mod code;
+mod rules;
+pub mod solver;
+
+pub use code::{score, Code, Colors, Feedback, ParseCodeError, PEG_COLORS};
+pub use rules::{
+    CodeError, Rules, RulesError, MAX_CODE_LENGTH, MAX_COLORS, MIN_CODE_LENGTH, MIN_COLORS,
+};
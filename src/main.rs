@@ -1,19 +1,33 @@
 extern crate piston_window;
 extern crate rand;
 
-use std::env;
+use std::{env, process};
 
 use piston_window::{
-    clear, types::Color, Button, PistonWindow, PressEvent, UpdateEvent, WindowSettings,
+    clear, types::Color, Button, MouseButton, MouseCursorEvent, PistonWindow, PressEvent,
+    Transformed, UpdateEvent, WindowSettings,
 };
 
-use crate::game::Game;
+use crate::{daily::Daily, game::Game, replay::Replay, save::SavedGame};
+use options::{Command, Frontend, USAGE};
 
 use draw::to_coord_u32;
 
+mod daily;
 mod draw;
+mod font;
 mod game;
 mod guess;
+mod help;
+mod history;
+mod net;
+mod options;
+mod replay;
+mod save;
+mod solve;
+mod storage;
+mod text;
+mod tui;
 
 pub const COLOR_RED: Color = [0.8, 0.0, 0.0, 1.0];
 pub const COLOR_GREEN: Color = [0.0, 0.8, 0.0, 1.0];
@@ -21,41 +35,142 @@ pub const COLOR_BLUE: Color = [0.0, 0.0, 0.8, 1.0];
 pub const COLOR_YELLOW: Color = [0.8, 0.8, 0.0, 1.0];
 pub const COLOR_BLACK: Color = [0.0, 0.0, 0.0, 1.0];
 pub const COLOR_WHITE: Color = [1.0, 1.0, 1.0, 1.0];
+pub const COLOR_ORANGE: Color = [1.0, 0.5, 0.0, 1.0];
+pub const COLOR_PURPLE: Color = [0.5, 0.0, 0.6, 1.0];
+pub const COLOR_CYAN: Color = [0.0, 0.8, 0.8, 1.0];
+pub const COLOR_BROWN: Color = [0.45, 0.25, 0.05, 1.0];
+pub const COLOR_PINK: Color = [1.0, 0.5, 0.7, 1.0];
+pub const COLOR_BLANK: Color = [0.3, 0.3, 0.3, 1.0];
+pub const COLOR_HOLE: Color = [0.1, 0.1, 0.1, 1.0];
 pub const COLOR_EMPTY: Color = [0.5, 0.5, 0.5, 1.0];
 pub const COLOR_SECRET: Color = [0.2, 0.2, 0.2, 1.0];
 pub const COLOR_SUCCESS: Color = [0.0, 1.0, 0.0, 0.5];
 pub const COLOR_GAMEOVER: Color = [1.0, 0.0, 0.0, 0.5];
+pub const COLOR_OVERLAY: Color = [0.0, 0.0, 0.0, 0.85];
+pub const COLOR_TEXT: Color = [1.0, 1.0, 1.0, 1.0];
 pub const SPACING: i32 = 3;
 pub const FIELD_SIZE: i32 = 2;
 
 const BACK_COLOR: Color = [0.4, 0.4, 0.4, 1.0];
 
 fn main() {
-    // parse arguments (for debugging)
-    let mut debug = false;
-    let args: Vec<String> = env::args().collect();
-    if args.len() > 1 {
-        if args[1] == "--debug" {
-            debug = true;
+    let mut options = match options::parse(env::args().skip(1)) {
+        Ok(Command::Play(options)) => options,
+        Ok(Command::Solve(options, secret)) => match solve::run(&options, secret) {
+            Ok(within_limit) => process::exit(if within_limit { 0 } else { 1 }),
+            Err(message) => {
+                eprintln!("error: {message}");
+                process::exit(2);
+            }
+        },
+        Ok(Command::Text(options, output)) => match text::run(&options, output) {
+            Ok(won) => process::exit(if won { 0 } else { 1 }),
+            Err(message) => {
+                eprintln!("error: {message}");
+                process::exit(2);
+            }
+        },
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            return;
         }
+        Err(message) => {
+            eprintln!("error: {message}\nrun `mastermind --help` for the options");
+            process::exit(2);
+        }
+    };
+
+    let connection = match options.network.clone() {
+        Some(network) => match net::open(&network, &mut options) {
+            Ok(connection) => Some(connection),
+            Err(error) => {
+                eprintln!("error: {error}");
+                process::exit(2);
+            }
+        },
+        None => None,
+    };
+
+    // a saved game brings its own rules
+    let saved = match options.resume {
+        true => {
+            match SavedGame::load().and_then(|saved| saved.apply(&mut options).map(|_| saved)) {
+                Ok(saved) => Some(saved),
+                Err(message) => {
+                    eprintln!("error: {message}");
+                    process::exit(2);
+                }
+            }
+        }
+        false => None,
+    };
+    // so does a replayed one
+    let replay = match options.replay {
+        Some(back) => match Replay::load(back) {
+            Ok(replay) => {
+                replay.apply(&mut options);
+                Some(replay)
+            }
+            Err(message) => {
+                eprintln!("error: {message}");
+                process::exit(2);
+            }
+        },
+        None => None,
+    };
+    // and a started daily the number of guesses it was started with
+    if options.daily {
+        Daily::today(options.rules).apply(&mut options);
+    }
+    let mut game = Game::new(&options, connection);
+    if let Some(saved) = saved {
+        if let Err(message) = game.resume(&saved) {
+            eprintln!("error: {message}");
+            process::exit(2);
+        }
+        if let Err(error) = SavedGame::remove() {
+            eprintln!("could not remove the saved game: {error}");
+        }
+    }
+    if let Some(replay) = replay {
+        game.start_replay(replay);
+    }
+
+    if options.frontend == Frontend::Terminal {
+        if let Err(error) = tui::run(game) {
+            eprintln!("error: {error}");
+            process::exit(2);
+        }
+        return;
     }
 
-    let (width, height) = (24, 39);
-    let mut window: PistonWindow =
-        WindowSettings::new("Mastermind", [to_coord_u32(width), to_coord_u32(height)])
-            .exit_on_esc(true)
-            .build()
-            .unwrap();
+    let (width, height) = Game::window_size(&options.rules, options.number_of_guesses);
+    let scale = options.scale;
+    let window_size = [
+        (to_coord_u32(width) as f64 * scale) as u32,
+        (to_coord_u32(height) as f64 * scale) as u32,
+    ];
+    let mut window: PistonWindow = WindowSettings::new(game.title(), window_size)
+        .exit_on_esc(true)
+        .build()
+        .unwrap();
 
-    let mut game = Game::new(width, height, 6, debug);
+    let mut cursor = [0.0, 0.0];
     while let Some(event) = window.next() {
-        if let Some(Button::Keyboard(key)) = event.press_args() {
-            game.key_pressed(key);
+        if let Some(position) = event.mouse_cursor_args() {
+            cursor = position;
+        }
+        match event.press_args() {
+            Some(Button::Keyboard(key)) => game.key_pressed(key),
+            Some(Button::Mouse(MouseButton::Left)) => {
+                game.mouse_pressed(cursor[0] / scale, cursor[1] / scale)
+            }
+            _ => {}
         }
 
         window.draw_2d(&event, |c, g, _| {
             clear(BACK_COLOR, g);
-            game.draw(&c, g);
+            game.draw(&c.scale(scale, scale), g);
         });
 
         event.update(|args| {
@@ -0,0 +1,282 @@
+//! Head to head over TCP: both players race to crack the same secret. The
+//! host draws the secret and scores every guess, so the client never learns
+//! it before the race is over.
+//!
+//! Messages are lines of text:
+//!
+//! - `rules <pegs> <colors> <guesses> <duplicates> <unique> <blanks>`, the
+//!   flags `0` or `1`, from the host right after the client connected
+//! - `guess <code>` from the client
+//! - `feedback <black> <white>` from the host, for the client's last guess
+//! - `rival <guesses> <black> <white>` from the host after each of its own
+//!   guesses
+//! - `over <won|lost|draw> <secret>` from the host once the race is decided,
+//!   seen from the client
+
+use std::{
+    fmt,
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+    str::FromStr,
+};
+
+use mastermind::{Code, Feedback, Rules};
+use serde::{Deserialize, Serialize};
+
+use crate::options::{Network, Options, MAX_GUESSES, MIN_GUESSES};
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+
+/// How a race ended for one side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Won,
+    Lost,
+    Draw,
+}
+
+impl Outcome {
+    /// The same outcome seen from the other side.
+    pub fn for_rival(self) -> Outcome {
+        match self {
+            Outcome::Won => Outcome::Lost,
+            Outcome::Lost => Outcome::Won,
+            Outcome::Draw => Outcome::Draw,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Outcome::Won => "won",
+            Outcome::Lost => "lost",
+            Outcome::Draw => "draw",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Rules(Rules, usize),
+    Guess(Code),
+    Feedback(Feedback),
+    Rival(usize, Feedback),
+    Over(Outcome, Code),
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Message::Rules(rules, guesses) => write!(
+                f,
+                "rules {} {} {guesses} {} {} {}",
+                rules.code_length,
+                rules.number_of_colors,
+                rules.allow_duplicates as u8,
+                rules.reject_duplicate_guesses as u8,
+                rules.allow_blanks as u8
+            ),
+            Message::Guess(guess) => write!(f, "guess {guess}"),
+            Message::Feedback(feedback) => {
+                write!(f, "feedback {} {}", feedback.black, feedback.white)
+            }
+            Message::Rival(guesses, feedback) => {
+                write!(f, "rival {guesses} {} {}", feedback.black, feedback.white)
+            }
+            Message::Over(outcome, secret) => write!(f, "over {} {secret}", outcome.name()),
+        }
+    }
+}
+
+impl FromStr for Message {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Message, String> {
+        let invalid = || format!("invalid message '{line}'");
+        let mut words = line.split_whitespace();
+        let kind = words.next().ok_or_else(invalid)?;
+        let mut number = || -> Result<usize, String> {
+            words
+                .next()
+                .and_then(|word| word.parse().ok())
+                .ok_or_else(invalid)
+        };
+        let message = match kind {
+            "rules" => {
+                let code_length = number()?;
+                let number_of_colors = number()?;
+                let guesses = number()?;
+                let rules = Rules {
+                    code_length,
+                    number_of_colors,
+                    allow_duplicates: number()? == 1,
+                    reject_duplicate_guesses: number()? == 1,
+                    allow_blanks: number()? == 1,
+                };
+                Message::Rules(rules, guesses)
+            }
+            "feedback" => Message::Feedback(Feedback {
+                black: number()?,
+                white: number()?,
+            }),
+            "rival" => Message::Rival(
+                number()?,
+                Feedback {
+                    black: number()?,
+                    white: number()?,
+                },
+            ),
+            "guess" => {
+                let guess = words.next().ok_or_else(invalid)?;
+                Message::Guess(guess.parse().map_err(|_| invalid())?)
+            }
+            "over" => {
+                let outcome = match words.next() {
+                    Some("won") => Outcome::Won,
+                    Some("lost") => Outcome::Lost,
+                    Some("draw") => Outcome::Draw,
+                    _ => return Err(invalid()),
+                };
+                let secret = words.next().ok_or_else(invalid)?;
+                Message::Over(outcome, secret.parse().map_err(|_| invalid())?)
+            }
+            _ => return Err(invalid()),
+        };
+        match words.next() {
+            Some(_) => Err(invalid()),
+            None => Ok(message),
+        }
+    }
+}
+
+pub struct Connection {
+    stream: TcpStream,
+    // bytes of a message that did not arrive completely yet
+    received: Vec<u8>,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> io::Result<Connection> {
+        stream.set_nodelay(true)?;
+        Ok(Connection {
+            stream,
+            received: vec![],
+        })
+    }
+
+    pub fn send(&mut self, message: &Message) -> io::Result<()> {
+        writeln!(self.stream, "{message}")
+    }
+
+    /// The next message, `None` if no whole message arrived yet. Waits for
+    /// one unless the stream is non-blocking. A message that can't be read
+    /// is an `InvalidData` error, the ones after it can still be received.
+    pub fn receive(&mut self) -> io::Result<Option<Message>> {
+        loop {
+            if let Some(end) = self.received.iter().position(|byte| *byte == b'\n') {
+                let line: Vec<u8> = self.received.drain(..=end).collect();
+                return String::from_utf8_lossy(&line)
+                    .trim()
+                    .parse()
+                    .map(Some)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error));
+            }
+            let mut buffer = [0; 512];
+            match self.stream.read(&mut buffer) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(read) => self.received.extend_from_slice(&buffer[..read]),
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+/// Connects as `network` says. The host waits for a player and sends it the
+/// rules, the client plays by the rules it gets and overwrites its own.
+pub fn open(network: &Network, options: &mut Options) -> io::Result<Connection> {
+    let connection = match network {
+        Network::Host(address) => {
+            let listener = TcpListener::bind(address)?;
+            println!("waiting for a player on {}", listener.local_addr()?);
+            let (stream, player) = listener.accept()?;
+            println!("{player} joined");
+            let mut connection = Connection::new(stream)?;
+            connection.send(&Message::Rules(options.rules, options.number_of_guesses))?;
+            connection
+        }
+        Network::Connect(address) => {
+            let mut connection = Connection::new(TcpStream::connect(address)?)?;
+            let Some(Message::Rules(rules, guesses)) = connection.receive()? else {
+                return Err(invalid_data("the host did not send its rules"));
+            };
+            rules
+                .validate()
+                .map_err(|error| invalid_data(&error.to_string()))?;
+            if !(MIN_GUESSES..=MAX_GUESSES).contains(&guesses) {
+                return Err(invalid_data("the host plays with too many guesses"));
+            }
+            options.rules = rules;
+            options.number_of_guesses = guesses;
+            connection
+        }
+    };
+    connection.stream.set_nonblocking(true)?;
+    Ok(connection)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_read_back_as_written() {
+        let rules = Rules {
+            code_length: 5,
+            number_of_colors: 8,
+            allow_duplicates: false,
+            reject_duplicate_guesses: true,
+            allow_blanks: true,
+        };
+        let feedback = Feedback { black: 2, white: 1 };
+        for message in [
+            Message::Rules(rules, 10),
+            Message::Rules(Rules::default(), 6),
+            Message::Guess("RGB_Y".parse().unwrap()),
+            Message::Feedback(feedback),
+            Message::Rival(3, feedback),
+            Message::Over(Outcome::Won, "RRGG".parse().unwrap()),
+            Message::Over(Outcome::Lost, "KWOV".parse().unwrap()),
+            Message::Over(Outcome::Draw, "CNPB".parse().unwrap()),
+        ] {
+            assert_eq!(message.to_string().parse(), Ok(message));
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        for line in [
+            "",
+            "hello",
+            "rules 4 6 6 1 0",
+            "rules 4 6 6 1 0 0 0",
+            "rules four 6 6 1 0 0",
+            "guess",
+            "guess RGBX",
+            "guess RGBY RGBY",
+            "feedback 1",
+            "feedback 1 -1",
+            "rival 2 1",
+            "over",
+            "over maybe RGBY",
+            "over won",
+            "over won RG?Y",
+        ] {
+            assert!(line.parse::<Message>().is_err(), "'{line}' was read");
+        }
+    }
+}
@@ -0,0 +1,516 @@
+use std::str::FromStr;
+
+use crate::net::DEFAULT_ADDRESS;
+use mastermind::{solver, Code, Rules, MAX_CODE_LENGTH, MAX_COLORS, MIN_CODE_LENGTH, MIN_COLORS};
+
+pub const MIN_GUESSES: usize = 1;
+pub const MAX_GUESSES: usize = 15;
+const MIN_SCALE: f64 = 0.5;
+const MAX_SCALE: f64 = 4.0;
+
+pub const USAGE: &str = "\
+Usage: mastermind [OPTIONS]
+
+Options:
+  -p, --pegs <N>       pegs per code, 3 to 8 (default 4)
+  -c, --colors <N>     number of peg colors, 2 to 11 (default 6)
+  -g, --guesses <N>    number of guesses, 1 to 15 (default 6)
+      --no-duplicates  every color appears at most once in the secret
+      --unique-guesses with --no-duplicates, reject guesses that repeat a color
+      --blanks         a blank hole is a legal peg in the secret and guesses
+      --seed <N>       replay the secrets of an earlier seed (shown in the title)
+      --daily          play today's puzzle, the same for everyone and only once
+      --scale <F>      window scale, 0.5 to 4 (default 1)
+      --tui            play in the terminal instead of a window
+      --assist         warn before playing a guess that contradicts earlier
+                       feedback, submit it again to play it anyway
+      --strict         like --assist, but such guesses can't be played
+      --codemaker      you set the secret and the solver cracks it
+      --enter-pins     with --codemaker, score the solver's guesses yourself
+      --hot-seat       two players take turns setting the secret for each
+                       other at one keyboard
+      --host [ADDR]    wait for a rival to race to the same secret, on
+                       127.0.0.1:7878 unless ADDR is given
+      --connect [ADDR] join the race of a host, playing by its rules
+      --resume         continue the game saved with S
+      --replay [N]     step through the last finished game again, or the one
+                       N games back
+      --debug          show the secret
+      --solve [CODE]   let the solver crack CODE (e.g. RGBY) or a random secret
+                       and print every guess, no window is opened
+      --text           read guesses like RGBY from stdin, one per line, and
+                       print their feedback, no window is opened
+      --json           like --text, but print JSON lines
+  -h, --help           print this help";
+
+/// Everything a game can be configured with from the command line.
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub rules: Rules,
+    pub number_of_guesses: usize,
+    pub seed: Option<u64>,
+    pub daily: bool,
+    pub scale: f64,
+    pub frontend: Frontend,
+    pub assist: Assist,
+    pub mode: Mode,
+    pub network: Option<Network>,
+    pub resume: bool,
+    /// Which finished game to replay, 1 for the last one.
+    pub replay: Option<usize>,
+    pub debug: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            rules: Rules::default(),
+            number_of_guesses: 6,
+            seed: None,
+            daily: false,
+            scale: 1.0,
+            frontend: Frontend::Window,
+            assist: Assist::Off,
+            mode: Mode::Codebreaker,
+            network: None,
+            resume: false,
+            replay: None,
+            debug: false,
+        }
+    }
+}
+
+/// Where the game is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frontend {
+    Window,
+    /// Drawn with ANSI colors in the terminal, no display server needed.
+    Terminal,
+}
+
+/// What happens to a guess that can't be the secret given the earlier
+/// feedback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assist {
+    /// It is played like any other guess.
+    Off,
+    /// The contradicted rows are shown, submitting again plays it.
+    Warn,
+    /// The contradicted rows are shown and it can't be played.
+    Block,
+}
+
+/// Who sets the secret and who cracks it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// The game sets a random secret for the player.
+    Codebreaker,
+    /// The player sets the secret for the solver, and scores its guesses
+    /// with `enter_pins`.
+    Codemaker { enter_pins: bool },
+    /// Two players take turns setting the secret for each other.
+    HotSeat,
+}
+
+/// Which side of a race over the network this game is, with the address to
+/// listen on or connect to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Network {
+    Host(String),
+    Connect(String),
+}
+
+/// How the line based text mode prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextOutput {
+    Plain,
+    Json,
+}
+
+pub enum Command {
+    Play(Options),
+    /// Let the solver play against the given or a random secret.
+    Solve(Options, Option<Code>),
+    /// Play with guesses from stdin and feedback on stdout.
+    Text(Options, TextOutput),
+    Help,
+}
+
+/// Parses the arguments without the program name, `--name value` and
+/// `--name=value` are both accepted.
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut options = Options::default();
+    let mut solve = None;
+    let mut enter_pins = false;
+    let mut text = None;
+    let mut args = args.into_iter().peekable();
+    while let Some(arg) = args.next() {
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .map(str::to_string)
+                .or_else(|| args.next())
+                .ok_or(format!("{name} needs a value"))
+        };
+        match name.as_str() {
+            "-p" | "--pegs" => {
+                options.rules.code_length =
+                    parse_in_range(&name, &value()?, MIN_CODE_LENGTH, MAX_CODE_LENGTH)?
+            }
+            "-c" | "--colors" => {
+                options.rules.number_of_colors =
+                    parse_in_range(&name, &value()?, MIN_COLORS, MAX_COLORS)?
+            }
+            "-g" | "--guesses" => {
+                options.number_of_guesses =
+                    parse_in_range(&name, &value()?, MIN_GUESSES, MAX_GUESSES)?
+            }
+            "--no-duplicates" => options.rules.allow_duplicates = false,
+            "--unique-guesses" => options.rules.reject_duplicate_guesses = true,
+            "--blanks" => options.rules.allow_blanks = true,
+            "--seed" => options.seed = Some(parse_value(&name, &value()?)?),
+            "--daily" => options.daily = true,
+            "--scale" => options.scale = parse_in_range(&name, &value()?, MIN_SCALE, MAX_SCALE)?,
+            "--assist" => options.assist = Assist::Warn,
+            "--strict" => options.assist = Assist::Block,
+            "--codemaker" => options.mode = Mode::Codemaker { enter_pins: false },
+            "--enter-pins" => enter_pins = true,
+            "--hot-seat" => options.mode = Mode::HotSeat,
+            "--host" | "--connect" => {
+                // the address is optional
+                let address = inline_value
+                    .map(str::to_string)
+                    .or_else(|| args.next_if(|next| !next.starts_with('-')))
+                    .unwrap_or(DEFAULT_ADDRESS.to_string());
+                options.network = Some(match name.as_str() {
+                    "--host" => Network::Host(address),
+                    _ => Network::Connect(address),
+                })
+            }
+            "--tui" => options.frontend = Frontend::Terminal,
+            "--resume" => options.resume = true,
+            "--replay" => {
+                // the number is optional
+                let back = inline_value
+                    .map(str::to_string)
+                    .or_else(|| args.next_if(|next| !next.starts_with('-')));
+                options.replay = match back {
+                    Some(back) => match parse_value(&name, &back)? {
+                        0 => return Err("--replay counts back from 1, the last game".to_string()),
+                        back => Some(back),
+                    },
+                    None => Some(1),
+                }
+            }
+            "--debug" => options.debug = true,
+            "--solve" => {
+                // the secret is optional
+                let secret = inline_value
+                    .map(str::to_string)
+                    .or_else(|| args.next_if(|next| !next.starts_with('-')));
+                solve = Some(secret)
+            }
+            "--text" => text = Some(TextOutput::Plain),
+            "--json" => text = Some(TextOutput::Json),
+            "-h" | "--help" => return Ok(Command::Help),
+            _ => return Err(format!("unknown argument '{arg}'")),
+        }
+    }
+    options
+        .rules
+        .validate()
+        .map_err(|error| error.to_string())?;
+    if options.daily && options.seed.is_some() {
+        return Err("--daily picks its own seed, it can't be combined with --seed".to_string());
+    }
+    if enter_pins {
+        match &mut options.mode {
+            Mode::Codemaker { enter_pins } => *enter_pins = true,
+            _ => return Err("--enter-pins only works with --codemaker".to_string()),
+        }
+    }
+    if options.daily && options.mode != Mode::Codebreaker {
+        return Err("the daily is always played as the codebreaker".to_string());
+    }
+    if options.network.is_some() && (options.daily || options.mode != Mode::Codebreaker) {
+        return Err("a race over the network is its own game, it can't be combined with --daily, --codemaker or --hot-seat".to_string());
+    }
+    if let Mode::Codemaker { .. } = options.mode {
+        solver::all_codes(&options.rules).map_err(|error| format!("--codemaker: {error}"))?;
+    }
+    if options.resume
+        && (options.daily || options.mode != Mode::Codebreaker || options.network.is_some())
+    {
+        return Err(
+            "only a game against a secret from the seed can be saved and resumed".to_string(),
+        );
+    }
+    if options.replay.is_some()
+        && (options.daily
+            || options.mode != Mode::Codebreaker
+            || options.network.is_some()
+            || options.resume
+            || solve.is_some()
+            || text.is_some())
+    {
+        return Err(
+            "--replay only shows a recorded game, it can't be combined with another game"
+                .to_string(),
+        );
+    }
+    if (solve.is_some() || text.is_some()) && options.frontend == Frontend::Terminal {
+        return Err(
+            "--solve and --text print lines, they can't be combined with --tui".to_string(),
+        );
+    }
+    if let Some(output) = text {
+        if solve.is_some() {
+            return Err("--solve and --text are different games, pick one".to_string());
+        }
+        if options.daily
+            || options.mode != Mode::Codebreaker
+            || options.network.is_some()
+            || options.resume
+        {
+            return Err(
+                "--text only plays the codebreaker against a secret from the seed".to_string(),
+            );
+        }
+        return Ok(Command::Text(options, output));
+    }
+    if solve.is_some()
+        && (options.daily
+            || options.mode != Mode::Codebreaker
+            || options.network.is_some()
+            || options.resume)
+    {
+        return Err(
+            "--solve plays against its own secret, it can't be combined with another game"
+                .to_string(),
+        );
+    }
+    match solve {
+        Some(Some(secret)) => {
+            let secret: Code = secret
+                .parse()
+                .map_err(|error| format!("--solve: {error}"))?;
+            options
+                .rules
+                .check_secret(&secret)
+                .map_err(|error| format!("--solve: {error}"))?;
+            Ok(Command::Solve(options, Some(secret)))
+        }
+        Some(None) => Ok(Command::Solve(options, None)),
+        None => Ok(Command::Play(options)),
+    }
+}
+
+fn parse_value<T: FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{value}' for {name}"))
+}
+
+fn parse_in_range<T>(name: &str, value: &str, min: T, max: T) -> Result<T, String>
+where
+    T: FromStr + PartialOrd + std::fmt::Display,
+{
+    let parsed = parse_value(name, value)?;
+    if parsed < min || parsed > max {
+        return Err(format!(
+            "{name} needs a value from {min} to {max}, got {value}"
+        ));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn options(args: &[&str]) -> Options {
+        match parse_args(args) {
+            Ok(Command::Play(options)) => options,
+            Ok(_) => panic!("{args:?} is not a game to play"),
+            Err(error) => panic!("{args:?} was rejected: {error}"),
+        }
+    }
+
+    fn assert_rejected(cases: &[&[&str]]) {
+        for args in cases {
+            assert!(parse_args(args).is_err(), "{args:?} was accepted");
+        }
+    }
+
+    #[test]
+    fn values_follow_with_a_space_or_an_equals_sign() {
+        for args in [
+            &["--pegs", "5", "--colors", "8", "--guesses", "10"][..],
+            &["--pegs=5", "--colors=8", "--guesses=10"],
+            &["-p", "5", "-c", "8", "-g", "10"],
+        ] {
+            let options = options(args);
+            assert_eq!(options.rules.code_length, 5);
+            assert_eq!(options.rules.number_of_colors, 8);
+            assert_eq!(options.number_of_guesses, 10);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_values() {
+        assert_rejected(&[
+            &["--pegs"],
+            &["--pegs", "9"],
+            &["--colors=1"],
+            &["--guesses", "many"],
+            &["--guesses", "16"],
+            &["--scale", "8"],
+            &["--frobnicate"],
+        ]);
+    }
+
+    #[test]
+    fn daily_picks_its_own_seed() {
+        assert!(options(&["--daily"]).daily);
+        assert_rejected(&[&["--daily", "--seed", "1"], &["--seed=1", "--daily"]]);
+    }
+
+    #[test]
+    fn duplicate_rules_must_fit_together() {
+        let rules = options(&["--no-duplicates", "--unique-guesses"]).rules;
+        assert!(!rules.allow_duplicates);
+        assert!(rules.reject_duplicate_guesses);
+        assert_rejected(&[
+            &["--unique-guesses"],
+            &["--no-duplicates", "--pegs", "5", "--colors", "4"],
+        ]);
+    }
+
+    #[test]
+    fn solve_takes_an_optional_secret() {
+        assert!(matches!(
+            parse_args(&["--solve"]),
+            Ok(Command::Solve(_, None))
+        ));
+        assert!(matches!(
+            parse_args(&["--solve", "--pegs", "4"]),
+            Ok(Command::Solve(_, None))
+        ));
+        for args in [&["--solve", "RGBY"][..], &["--solve=RGBY"]] {
+            match parse_args(args) {
+                Ok(Command::Solve(_, Some(secret))) => {
+                    assert_eq!(secret, "RGBY".parse().unwrap())
+                }
+                _ => panic!("{args:?} didn't solve RGBY"),
+            }
+        }
+        assert_rejected(&[
+            &["--solve", "RGB"],
+            &["--solve", "RGBX"],
+            &["--solve", "--daily"],
+            &["--solve", "--codemaker"],
+            &["--solve", "--hot-seat"],
+            &["--solve", "--host"],
+            &["--solve", "RGBY", "--connect"],
+            &["--solve", "--resume"],
+            &["--solve", "--tui"],
+        ]);
+    }
+
+    #[test]
+    fn enter_pins_needs_the_codemaker() {
+        assert_eq!(
+            options(&["--codemaker", "--enter-pins"]).mode,
+            Mode::Codemaker { enter_pins: true }
+        );
+        assert_rejected(&[
+            &["--enter-pins"],
+            &["--daily", "--codemaker"],
+            &["--codemaker", "--pegs", "8", "--colors", "11"],
+        ]);
+    }
+
+    #[test]
+    fn hot_seat_is_its_own_game() {
+        assert_eq!(options(&["--hot-seat"]).mode, Mode::HotSeat);
+        assert_rejected(&[&["--hot-seat", "--enter-pins"], &["--daily", "--hot-seat"]]);
+    }
+
+    #[test]
+    fn host_and_connect_take_an_optional_address() {
+        let address = |args: &[&str]| options(args).network;
+        let default = DEFAULT_ADDRESS.to_string();
+        assert_eq!(address(&["--host"]), Some(Network::Host(default.clone())));
+        assert_eq!(
+            address(&["--host", "--pegs", "4"]),
+            Some(Network::Host(default))
+        );
+        assert_eq!(
+            address(&["--host", "0.0.0.0:9000"]),
+            Some(Network::Host("0.0.0.0:9000".to_string()))
+        );
+        assert_eq!(
+            address(&["--connect=10.0.0.2:7878"]),
+            Some(Network::Connect("10.0.0.2:7878".to_string()))
+        );
+        assert_rejected(&[
+            &["--host", "--daily"],
+            &["--connect", "--codemaker"],
+            &["--host", "--hot-seat"],
+        ]);
+    }
+
+    #[test]
+    fn text_plays_the_codebreaker_only() {
+        assert!(matches!(
+            parse_args(&["--json", "--pegs", "5"]),
+            Ok(Command::Text(_, TextOutput::Json))
+        ));
+        assert_rejected(&[
+            &["--text", "--solve"],
+            &["--json", "--solve", "RGBY"],
+            &["--text", "--daily"],
+            &["--text", "--codemaker"],
+            &["--json", "--hot-seat"],
+            &["--text", "--host"],
+            &["--text", "--resume"],
+            &["--json", "--tui"],
+        ]);
+    }
+
+    #[test]
+    fn resume_continues_a_game_from_the_seed() {
+        assert!(options(&["--resume"]).resume);
+        assert_rejected(&[
+            &["--resume", "--daily"],
+            &["--resume", "--codemaker"],
+            &["--resume", "--hot-seat"],
+            &["--resume", "--host"],
+        ]);
+    }
+
+    #[test]
+    fn replay_takes_an_optional_number() {
+        assert_eq!(options(&["--replay"]).replay, Some(1));
+        assert_eq!(options(&["--replay", "--debug"]).replay, Some(1));
+        assert_eq!(options(&["--replay", "3"]).replay, Some(3));
+        assert_eq!(options(&["--replay=2"]).replay, Some(2));
+        assert_rejected(&[
+            &["--replay", "0"],
+            &["--replay", "--daily"],
+            &["--replay", "--codemaker"],
+            &["--replay", "--hot-seat"],
+            &["--replay", "--connect"],
+            &["--replay", "--resume"],
+            &["--replay", "--solve"],
+            &["--replay", "--text"],
+        ]);
+    }
+}
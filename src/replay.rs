@@ -0,0 +1,271 @@
+//! Watching a game from the history again, one guess at a time.
+
+use mastermind::{
+    solver::{Solver, SolverError},
+    Code, Feedback, Rules,
+};
+
+use crate::{
+    history::{self, Record},
+    options::Options,
+};
+
+/// What the solver makes of the guesses shown so far.
+pub struct Insight {
+    pub candidates: usize,
+    /// The solver's next guess, `None` once the secret is cracked.
+    pub best_guess: Option<Code>,
+}
+
+pub struct Replay {
+    time: String,
+    rules: Rules,
+    number_of_guesses: usize,
+    secret: Code,
+    rows: Vec<(Code, Feedback)>,
+    // how many rows are shown
+    step: usize,
+    // kept while the solver's view is shown, for the current step
+    insight: Option<Result<Insight, SolverError>>,
+}
+
+impl Replay {
+    /// The game `back` games ago in the history, 1 for the last one.
+    pub fn load(back: usize) -> Result<Replay, String> {
+        let records = history::load().map_err(|error| error.to_string())?;
+        Replay::of(&records, back)
+    }
+
+    fn of(records: &[Record], back: usize) -> Result<Replay, String> {
+        let record = records
+            .len()
+            .checked_sub(back)
+            .map(|index| &records[index])
+            .ok_or_else(|| match records.len() {
+                0 => "no game was recorded yet".to_string(),
+                1 => "only 1 game was recorded".to_string(),
+                count => format!("only {count} games were recorded"),
+            })?;
+
+        let damaged =
+            |problem: &str| format!("the game of {} is damaged: {problem}", record.time());
+        let setup = record.setup();
+        setup.validate().map_err(|error| damaged(&error))?;
+        let rules = setup.rules();
+        let number_of_guesses = setup.number_of_guesses();
+        let rows = record.rows().map_err(|error| damaged(&error))?;
+        if rows.len() > number_of_guesses {
+            return Err(damaged("more guesses than allowed"));
+        }
+        let secret = record.secret().map_err(|error| damaged(&error))?;
+        let wrong_length = |code: &Code| code.len() != rules.code_length;
+        if wrong_length(&secret) || rows.iter().any(|(guess, _)| wrong_length(guess)) {
+            return Err(damaged("wrong number of pegs"));
+        }
+        Ok(Replay {
+            time: record.time().to_string(),
+            rules,
+            number_of_guesses,
+            secret,
+            rows,
+            step: 0,
+            insight: None,
+        })
+    }
+
+    /// Plays by the rules of the recorded game.
+    pub fn apply(&self, options: &mut Options) {
+        options.rules = self.rules;
+        options.number_of_guesses = self.number_of_guesses;
+    }
+
+    pub fn time(&self) -> &str {
+        &self.time
+    }
+
+    pub fn secret(&self) -> &Code {
+        &self.secret
+    }
+
+    /// The rows up to the current step.
+    pub fn shown(&self) -> &[(Code, Feedback)] {
+        &self.rows[..self.step]
+    }
+
+    /// How many guesses the recorded game took.
+    pub fn guesses(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.step == self.rows.len()
+    }
+
+    pub fn cracked(&self) -> bool {
+        self.rows
+            .last()
+            .is_some_and(|(_, feedback)| feedback.black == self.rules.code_length)
+    }
+
+    pub fn forward(&mut self) {
+        if !self.at_end() {
+            self.step += 1;
+            self.update_insight();
+        }
+    }
+
+    pub fn back(&mut self) {
+        if self.step > 0 {
+            self.step -= 1;
+            self.update_insight();
+        }
+    }
+
+    pub fn insight(&self) -> Option<&Result<Insight, SolverError>> {
+        self.insight.as_ref()
+    }
+
+    /// Shows or hides what the solver makes of each step.
+    pub fn toggle_insight(&mut self) {
+        self.insight = match self.insight {
+            Some(_) => None,
+            None => Some(self.solve_step()),
+        }
+    }
+
+    fn update_insight(&mut self) {
+        if self.insight.is_some() {
+            self.insight = Some(self.solve_step());
+        }
+    }
+
+    fn solve_step(&self) -> Result<Insight, SolverError> {
+        let mut solver = Solver::new(&self.rules)?;
+        for (guess, feedback) in self.shown() {
+            solver.add_feedback(guess, *feedback);
+        }
+        let cracked = self.at_end() && self.cracked();
+        Ok(Insight {
+            candidates: solver.candidates().len(),
+            best_guess: (!cracked).then(|| solver.next_guess()).flatten(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{Played, Setup};
+
+    fn record(secret: &str, guesses: &[&str]) -> Record {
+        let secret: Code = secret.parse().unwrap();
+        let rows: Vec<_> = guesses
+            .iter()
+            .map(|guess| {
+                let guess: Code = guess.parse().unwrap();
+                let feedback = mastermind::score(&secret, &guess);
+                (guess, feedback)
+            })
+            .collect();
+        let setup = Setup::new(&Rules::default(), 6);
+        Record::new(Played::Codebreaker, setup, &secret, &rows, 0, None, 60)
+    }
+
+    /// A recorded game as it could be found in the history file.
+    fn damaged(changes: &[(&str, &str)]) -> Record {
+        let mut json = serde_json::to_string(&record("RGBY", &["RRGG"])).unwrap();
+        for (from, to) in changes {
+            assert!(json.contains(from), "{json} has no {from}");
+            json = json.replace(from, to);
+        }
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn picks_the_game_counted_back_from_the_last() {
+        let records = [record("RGBY", &["RGBY"]), record("OOPP", &[])];
+        assert_eq!(
+            Replay::of(&records, 1).unwrap().secret().to_string(),
+            "OOPP"
+        );
+        assert_eq!(
+            Replay::of(&records, 2).unwrap().secret().to_string(),
+            "RGBY"
+        );
+        assert_eq!(
+            Replay::of(&records, 3).err().unwrap(),
+            "only 2 games were recorded"
+        );
+        assert_eq!(
+            Replay::of(&[], 1).err().unwrap(),
+            "no game was recorded yet"
+        );
+    }
+
+    #[test]
+    fn rejects_damaged_games() {
+        assert!(Replay::of(&[damaged(&[])], 1).is_ok());
+        for changes in [
+            &[(r#""colors":6"#, r#""colors":1"#)][..],
+            &[(r#""guesses":6"#, r#""guesses":0"#)],
+            &[(r#""guesses":6"#, r#""guesses":99"#)],
+            &[(r#""secret":"RGBY""#, r#""secret":"RGB""#)],
+            &[(r#""secret":"RGBY""#, r#""secret":"R?BY""#)],
+            &[(r#""guess":"RRGG""#, r#""guess":"RRGGB""#)],
+        ] {
+            let error = Replay::of(&[damaged(changes)], 1).err();
+            assert!(
+                error.is_some_and(|error| error.contains("is damaged")),
+                "{changes:?} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_more_guesses_than_the_game_had() {
+        let guesses = ["RRGG"; 7];
+        let error = Replay::of(&[record("RGBY", &guesses)], 1).err().unwrap();
+        assert!(error.ends_with("more guesses than allowed"), "{error}");
+    }
+
+    #[test]
+    fn steps_stop_at_both_ends() {
+        let mut replay = Replay::of(&[record("RGBY", &["RRGG", "RGBY"])], 1).unwrap();
+        replay.back();
+        assert!(replay.shown().is_empty());
+        replay.forward();
+        replay.forward();
+        assert!(replay.at_end());
+        replay.forward();
+        assert_eq!(replay.shown().len(), 2);
+        assert!(replay.cracked());
+        replay.back();
+        assert_eq!(replay.shown().len(), 1);
+        assert!(!replay.at_end());
+    }
+
+    #[test]
+    fn insight_follows_the_steps() {
+        let mut replay = Replay::of(&[record("RGBY", &["RRGG", "RGBY"])], 1).unwrap();
+        replay.toggle_insight();
+        let insight = replay.insight().unwrap().as_ref().unwrap();
+        assert_eq!(insight.candidates, 6usize.pow(4));
+        assert!(insight.best_guess.is_some());
+
+        replay.forward();
+        let insight = replay.insight().unwrap().as_ref().unwrap();
+        assert!(insight.candidates < 6usize.pow(4));
+        assert!(insight.best_guess.is_some());
+
+        // nothing is left to guess once the secret is cracked
+        replay.forward();
+        let insight = replay.insight().unwrap().as_ref().unwrap();
+        assert_eq!(insight.candidates, 1);
+        assert_eq!(insight.best_guess, None);
+
+        replay.toggle_insight();
+        assert!(replay.insight().is_none());
+        replay.back();
+        assert!(replay.insight().is_none());
+    }
+}
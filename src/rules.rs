@@ -0,0 +1,165 @@
+use std::fmt;
+
+use crate::{code::PEG_COLORS, Code, Colors};
+
+pub const MIN_CODE_LENGTH: usize = 3;
+pub const MAX_CODE_LENGTH: usize = 8;
+pub const MIN_COLORS: usize = 2;
+pub const MAX_COLORS: usize = PEG_COLORS.len();
+
+/// The settings a game is played with, shared by the secret generator, the
+/// scorer and every frontend.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Rules {
+    pub code_length: usize,
+    pub number_of_colors: usize,
+    pub allow_duplicates: bool,
+    /// Only with `allow_duplicates` off: guesses may not repeat colors either.
+    pub reject_duplicate_guesses: bool,
+    /// A blank hole is a legal symbol in the secret and in guesses.
+    pub allow_blanks: bool,
+}
+
+impl Rules {
+    /// The colors a code of this game can be made of, blank included if
+    /// allowed.
+    pub fn playable_colors(&self) -> Vec<Colors> {
+        let mut playable_colors = PEG_COLORS[..self.number_of_colors].to_vec();
+        if self.allow_blanks {
+            playable_colors.push(Colors::Blank);
+        }
+        playable_colors
+    }
+
+    /// Colors an input slot can cycle through, the empty slot followed by the
+    /// playable colors.
+    pub fn color_options(&self) -> Vec<Colors> {
+        let mut color_options = vec![Colors::Empty];
+        color_options.extend(self.playable_colors());
+        color_options
+    }
+
+    /// Checks that a secret can be made with these settings.
+    pub fn validate(&self) -> Result<(), RulesError> {
+        if !(MIN_CODE_LENGTH..=MAX_CODE_LENGTH).contains(&self.code_length) {
+            return Err(RulesError::CodeLength(self.code_length));
+        }
+        if !(MIN_COLORS..=MAX_COLORS).contains(&self.number_of_colors) {
+            return Err(RulesError::NumberOfColors(self.number_of_colors));
+        }
+        let number_of_symbols = self.playable_colors().len();
+        if !self.allow_duplicates && number_of_symbols < self.code_length {
+            return Err(RulesError::NotEnoughColors {
+                code_length: self.code_length,
+                number_of_colors: number_of_symbols,
+            });
+        }
+        if self.allow_duplicates && self.reject_duplicate_guesses {
+            return Err(RulesError::DuplicateGuessesWithDuplicateSecret);
+        }
+        Ok(())
+    }
+
+    /// Checks that `secret` is a code the secret generator could have drawn.
+    pub fn check_secret(&self, secret: &Code) -> Result<(), CodeError> {
+        self.check_code(secret, !self.allow_duplicates)
+    }
+
+    /// Checks that `guess` is a code a player may submit.
+    pub fn check_guess(&self, guess: &Code) -> Result<(), CodeError> {
+        self.check_code(guess, self.reject_duplicate_guesses)
+    }
+
+    fn check_code(&self, code: &Code, reject_duplicates: bool) -> Result<(), CodeError> {
+        if code.len() != self.code_length {
+            return Err(CodeError::Length {
+                expected: self.code_length,
+                found: code.len(),
+            });
+        }
+        let playable_colors = self.playable_colors();
+        if let Some(color) = code.pegs().iter().find(|c| !playable_colors.contains(c)) {
+            return Err(CodeError::Color(*color));
+        }
+        if reject_duplicates && code.has_duplicates() {
+            return Err(CodeError::Duplicates);
+        }
+        Ok(())
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            code_length: 4,
+            number_of_colors: 6,
+            allow_duplicates: true,
+            reject_duplicate_guesses: false,
+            allow_blanks: false,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum RulesError {
+    CodeLength(usize),
+    NumberOfColors(usize),
+    NotEnoughColors {
+        code_length: usize,
+        number_of_colors: usize,
+    },
+    DuplicateGuessesWithDuplicateSecret,
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesError::CodeLength(code_length) => write!(
+                f,
+                "code length {code_length} is not between {MIN_CODE_LENGTH} and {MAX_CODE_LENGTH}"
+            ),
+            RulesError::NumberOfColors(number_of_colors) => write!(
+                f,
+                "{number_of_colors} colors is not between {MIN_COLORS} and {MAX_COLORS}"
+            ),
+            RulesError::NotEnoughColors {
+                code_length,
+                number_of_colors,
+            } => write!(
+                f,
+                "a code of {code_length} pegs without duplicates needs at least \
+                 {code_length} colors, not {number_of_colors}"
+            ),
+            RulesError::DuplicateGuessesWithDuplicateSecret => write!(
+                f,
+                "duplicate guesses can only be rejected if the secret has no duplicates"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+/// Why a code can't be used as the secret of a game.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum CodeError {
+    Length { expected: usize, found: usize },
+    Color(Colors),
+    Duplicates,
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodeError::Length { expected, found } => {
+                write!(f, "the code needs {expected} pegs, not {found}")
+            }
+            CodeError::Color(color) => {
+                write!(f, "'{}' is not one of the colors in play", color.to_char())
+            }
+            CodeError::Duplicates => write!(f, "the code may not repeat a color"),
+        }
+    }
+}
+
+impl std::error::Error for CodeError {}
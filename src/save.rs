@@ -0,0 +1,248 @@
+//! A game saved to continue later, as JSON in `save.json` in the data
+//! directory:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "pegs": 4,
+//!   "colors": 6,
+//!   "duplicates": true,
+//!   "unique_guesses": false,
+//!   "blanks": false,
+//!   "guesses": 6,
+//!   "seed": 42,
+//!   "round": 1,
+//!   "salt": 9017512344006352,
+//!   "secret": "a3f0c911",
+//!   "rows": [{ "guess": "RRBB", "black": 1, "white": 0 }],
+//!   "hints_used": 0,
+//!   "seconds": 95
+//! }
+//! ```
+//!
+//! `seed` and `round` tell how many secrets were drawn from the seed, so
+//! restarting after resuming draws the same secrets as before, `seconds` how
+//! long the game was played so far. `secret` is the letters of the secret
+//! XORed with a key stream generated from `salt`, hex encoded. That keeps the
+//! secret from being read by accident, not from a player determined to cheat.
+
+use std::{fs, io, path::PathBuf};
+
+use mastermind::{Code, Feedback};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    history::{Row, Setup},
+    options::Options,
+    storage,
+};
+
+const SAVE_FILE: &str = "save.json";
+const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedGame {
+    version: u32,
+    #[serde(flatten)]
+    setup: Setup,
+    pub seed: u64,
+    pub round: usize,
+    salt: u64,
+    secret: String,
+    rows: Vec<Row>,
+    pub hints_used: usize,
+    #[serde(default)]
+    pub seconds: u64,
+}
+
+impl SavedGame {
+    pub fn new(
+        setup: Setup,
+        seed: u64,
+        round: usize,
+        secret: &Code,
+        rows: &[(Code, Feedback)],
+        hints_used: usize,
+        seconds: u64,
+    ) -> SavedGame {
+        let salt = rand::random();
+        SavedGame {
+            version: VERSION,
+            setup,
+            seed,
+            round,
+            salt,
+            secret: obfuscate(&secret.to_string(), salt),
+            rows: rows
+                .iter()
+                .map(|(guess, feedback)| Row::new(guess, *feedback))
+                .collect(),
+            hints_used,
+            seconds,
+        }
+    }
+
+    /// Writes the game to the save file, replacing an earlier save.
+    pub fn store(&self) -> io::Result<PathBuf> {
+        let path = storage::data_file(SAVE_FILE)?;
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(&path, json)?;
+        Ok(path)
+    }
+
+    pub fn load() -> Result<SavedGame, String> {
+        let path = storage::data_file(SAVE_FILE).map_err(|error| error.to_string())?;
+        let json = fs::read_to_string(&path)
+            .map_err(|error| format!("no saved game in {}: {error}", path.display()))?;
+        let saved: SavedGame = serde_json::from_str(&json)
+            .map_err(|error| format!("{} is not a saved game: {error}", path.display()))?;
+        if saved.version != VERSION {
+            return Err(format!(
+                "saved games of version {} can't be resumed",
+                saved.version
+            ));
+        }
+        Ok(saved)
+    }
+
+    /// Deletes the save file once its game was resumed, so a game can't be
+    /// played again from the same save.
+    pub fn remove() -> io::Result<()> {
+        fs::remove_file(storage::data_file(SAVE_FILE)?)
+    }
+
+    /// Plays by the saved rules and seed instead of the command line ones.
+    pub fn apply(&self, options: &mut Options) -> Result<(), String> {
+        self.setup
+            .validate()
+            .map_err(|error| format!("the saved game is damaged: {error}"))?;
+        options.rules = self.setup.rules();
+        options.number_of_guesses = self.setup.number_of_guesses();
+        options.seed = Some(self.seed);
+        Ok(())
+    }
+
+    /// The saved secret, checked against the saved rules.
+    pub fn secret(&self) -> Result<Code, String> {
+        let secret: Code = deobfuscate(&self.secret, self.salt)
+            .and_then(|secret| secret.parse().ok())
+            .ok_or_else(|| "the saved secret is damaged".to_string())?;
+        self.setup
+            .rules()
+            .check_secret(&secret)
+            .map_err(|error| format!("the saved secret is damaged: {error}"))?;
+        Ok(secret)
+    }
+
+    /// The saved guesses, checked against the rules and the secret.
+    pub fn rows(&self) -> Result<Vec<Code>, String> {
+        let rules = self.setup.rules();
+        let secret = self.secret()?;
+        self.rows
+            .iter()
+            .map(|row| {
+                let guess = row.guess()?;
+                rules
+                    .check_guess(&guess)
+                    .map_err(|error| format!("the saved guess {guess} is damaged: {error}"))?;
+                if mastermind::score(&secret, &guess) != row.feedback() {
+                    return Err(format!("the feedback saved for {guess} is wrong"));
+                }
+                Ok(guess)
+            })
+            .collect()
+    }
+}
+
+fn key_stream(salt: u64) -> impl Iterator<Item = u8> {
+    let mut rng = StdRng::seed_from_u64(salt);
+    std::iter::repeat_with(move || rng.gen())
+}
+
+fn obfuscate(text: &str, salt: u64) -> String {
+    text.bytes()
+        .zip(key_stream(salt))
+        .map(|(byte, key)| format!("{:02x}", byte ^ key))
+        .collect()
+}
+
+fn deobfuscate(hex: &str, salt: u64) -> Option<String> {
+    let bytes: Option<Vec<u8>> = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect();
+    let bytes = bytes?
+        .into_iter()
+        .zip(key_stream(salt))
+        .map(|(byte, key)| byte ^ key)
+        .collect();
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::{MAX_GUESSES, MIN_GUESSES};
+    use mastermind::Rules;
+
+    #[test]
+    fn obfuscated_text_reads_back() {
+        for (text, salt) in [("RGBY", 0), ("RGB_YKWO", 42), ("", 7), ("PPPP", u64::MAX)] {
+            let hidden = obfuscate(text, salt);
+            assert_eq!(hidden.len(), 2 * text.len());
+            assert_eq!(deobfuscate(&hidden, salt).as_deref(), Some(text));
+        }
+        assert_ne!(obfuscate("RGBY", 1), obfuscate("RGBY", 2));
+        assert_eq!(deobfuscate("a3f", 1), None);
+        assert_eq!(deobfuscate("zz", 1), None);
+    }
+
+    #[test]
+    fn rows_reject_tampered_feedback() {
+        let secret: Code = "RGBY".parse().unwrap();
+        let guess: Code = "RRGG".parse().unwrap();
+        let rows = [(guess.clone(), mastermind::score(&secret, &guess))];
+        let mut saved = SavedGame::new(
+            Setup::new(&Rules::default(), 6),
+            42,
+            1,
+            &secret,
+            &rows,
+            0,
+            30,
+        );
+        assert_eq!(saved.secret(), Ok(secret));
+        assert_eq!(saved.rows(), Ok(vec![guess.clone()]));
+
+        saved.rows[0] = Row::new(&guess, Feedback { black: 4, white: 0 });
+        assert!(saved.rows().is_err());
+    }
+
+    #[test]
+    fn rows_reject_guesses_against_the_rules() {
+        let secret: Code = "RGBY".parse().unwrap();
+        let mut saved =
+            SavedGame::new(Setup::new(&Rules::default(), 6), 42, 1, &secret, &[], 0, 30);
+        for guess in ["RG", "RGBYR", "RGBO"] {
+            saved.rows = vec![Row::new(&guess.parse().unwrap(), Feedback::default())];
+            assert!(saved.rows().is_err(), "{guess} was accepted");
+        }
+    }
+
+    #[test]
+    fn apply_rejects_a_wrong_number_of_guesses() {
+        let secret: Code = "RGBY".parse().unwrap();
+        let mut saved =
+            SavedGame::new(Setup::new(&Rules::default(), 6), 42, 1, &secret, &[], 0, 30);
+        let mut options = Options::default();
+        for guesses in [MIN_GUESSES - 1, MAX_GUESSES + 1] {
+            saved.setup = Setup::new(&Rules::default(), guesses);
+            assert!(saved.apply(&mut options).is_err());
+        }
+        saved.setup = Setup::new(&Rules::default(), MAX_GUESSES);
+        assert!(saved.apply(&mut options).is_ok());
+        assert_eq!(options.number_of_guesses, MAX_GUESSES);
+        assert_eq!(options.seed, Some(42));
+    }
+}
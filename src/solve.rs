@@ -0,0 +1,43 @@
+use mastermind::{solver::Solver, Code};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::options::Options;
+
+/// Lets the solver play against `secret` (or a secret drawn from the seed)
+/// and prints every guess. Returns whether it stayed within the number of
+/// guesses of the game.
+pub fn run(options: &Options, secret: Option<Code>) -> Result<bool, String> {
+    let rules = &options.rules;
+    let secret = secret.unwrap_or_else(|| {
+        let seed = options.seed.unwrap_or_else(rand::random);
+        println!("seed {seed}");
+        Code::random(rules, &mut StdRng::seed_from_u64(seed))
+    });
+    let mut solver = Solver::new(rules).map_err(|error| error.to_string())?;
+
+    println!("secret {secret}");
+    let rows = solver.solve(&secret);
+    for (i, (guess, feedback)) in rows.iter().enumerate() {
+        println!(
+            "{}: {guess}  black {} white {}",
+            i + 1,
+            feedback.black,
+            feedback.white
+        );
+    }
+
+    let limit = options.number_of_guesses;
+    if rows.len() <= limit {
+        println!(
+            "solved in {} guesses, within the limit of {limit}",
+            rows.len()
+        );
+        Ok(true)
+    } else {
+        println!(
+            "solved in {} guesses, over the limit of {limit}",
+            rows.len()
+        );
+        Ok(false)
+    }
+}
@@ -0,0 +1,172 @@
+//! Knuth's minimax algorithm, which solves the standard 4 peg / 6 color game
+//! in at most five guesses.
+//!
+//! The solver keeps every code that is still consistent with the feedback so
+//! far and picks the guess whose worst case feedback leaves the fewest of
+//! them. Larger games fall back to cheaper guesses once a full minimax search
+//! gets too expensive.
+
+use std::fmt;
+
+use crate::{score, Code, Colors, Feedback, Rules};
+
+/// The most codes a game may have for the solver to enumerate them.
+pub const MAX_CODES: usize = 500_000;
+// scorings one minimax search may do before falling back to cheaper guesses
+const MINIMAX_BUDGET: usize = 5_000_000;
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum SolverError {
+    TooManyCodes,
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolverError::TooManyCodes => write!(
+                f,
+                "these rules allow more than {MAX_CODES} codes, too many to solve"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+/// Every code a secret can be under `rules`, in palette order.
+pub fn all_codes(rules: &Rules) -> Result<Vec<Code>, SolverError> {
+    let colors = rules.playable_colors();
+    // count first, the enumeration itself would take forever
+    let mut count: usize = 1;
+    for i in 0..rules.code_length {
+        let choices = match rules.allow_duplicates {
+            true => colors.len(),
+            false => colors.len().saturating_sub(i),
+        };
+        count = count
+            .checked_mul(choices)
+            .filter(|count| *count <= MAX_CODES)
+            .ok_or(SolverError::TooManyCodes)?;
+    }
+
+    let mut codes = Vec::with_capacity(count);
+    let mut indices = vec![0; rules.code_length];
+    loop {
+        let pegs: Vec<Colors> = indices.iter().map(|i| colors[*i]).collect();
+        let code = Code::new(pegs);
+        if rules.allow_duplicates || !code.has_duplicates() {
+            codes.push(code);
+        }
+        // count up like an odometer, the last peg turning fastest
+        let mut position = rules.code_length;
+        loop {
+            if position == 0 {
+                return Ok(codes);
+            }
+            position -= 1;
+            indices[position] += 1;
+            if indices[position] < colors.len() {
+                break;
+            }
+            indices[position] = 0;
+        }
+    }
+}
+
+pub struct Solver {
+    rules: Rules,
+    all_codes: Vec<Code>,
+    candidates: Vec<Code>,
+    guesses: usize,
+}
+
+impl Solver {
+    pub fn new(rules: &Rules) -> Result<Solver, SolverError> {
+        let all_codes = all_codes(rules)?;
+        Ok(Solver {
+            rules: *rules,
+            candidates: all_codes.clone(),
+            all_codes,
+            guesses: 0,
+        })
+    }
+
+    /// The codes that are still consistent with all feedback so far.
+    pub fn candidates(&self) -> &[Code] {
+        &self.candidates
+    }
+
+    /// Narrows the candidates down to the codes that would have given
+    /// `feedback` for `guess`.
+    pub fn add_feedback(&mut self, guess: &Code, feedback: Feedback) {
+        self.candidates
+            .retain(|candidate| score(candidate, guess) == feedback);
+        self.guesses += 1;
+    }
+
+    /// The best next guess, `None` if no code fits the feedback any more.
+    pub fn next_guess(&self) -> Option<Code> {
+        match self.candidates.len() {
+            0 => None,
+            1 => Some(self.candidates[0].clone()),
+            _ if self.guesses == 0 && self.rules.allow_duplicates => Some(self.first_guess()),
+            candidates if self.all_codes.len() * candidates <= MINIMAX_BUDGET => {
+                Some(self.minimax(&self.all_codes))
+            }
+            candidates if candidates * candidates <= MINIMAX_BUDGET => {
+                Some(self.minimax(&self.candidates))
+            }
+            _ => Some(self.candidates[0].clone()),
+        }
+    }
+
+    /// Plays a whole game against `secret`, returning every guess with its
+    /// feedback. Stops early if the solver runs out of candidates, which only
+    /// happens if `secret` is not a code of these rules.
+    pub fn solve(&mut self, secret: &Code) -> Vec<(Code, Feedback)> {
+        let mut rows = vec![];
+        while let Some(guess) = self.next_guess() {
+            let feedback = score(secret, &guess);
+            self.add_feedback(&guess, feedback);
+            rows.push((guess, feedback));
+            if feedback.black == self.rules.code_length {
+                break;
+            }
+        }
+        rows
+    }
+
+    /// Knuth's opening `1122`, half of the pegs in the first color and the
+    /// rest in the second.
+    fn first_guess(&self) -> Code {
+        let colors = self.rules.playable_colors();
+        let half = self.rules.code_length.div_ceil(2);
+        Code::new(
+            (0..self.rules.code_length)
+                .map(|i| if i < half { colors[0] } else { colors[1] })
+                .collect(),
+        )
+    }
+
+    /// The guess out of `pool` with the smallest worst case, preferring
+    /// candidates and then the earliest code on ties.
+    fn minimax(&self, pool: &[Code]) -> Code {
+        let pegs = self.rules.code_length + 1;
+        let solved = self.rules.code_length * pegs;
+        let mut best: Option<((usize, bool), &Code)> = None;
+        for guess in pool {
+            let mut partitions = vec![0; pegs * pegs];
+            for candidate in &self.candidates {
+                let feedback = score(candidate, guess);
+                partitions[feedback.black * pegs + feedback.white] += 1;
+            }
+            let worst_case = *partitions.iter().max().unwrap();
+            // only a candidate can be scored all black
+            let rank = (worst_case, partitions[solved] == 0);
+            if best.is_none_or(|(best_rank, _)| rank < best_rank) {
+                best = Some((rank, guess));
+            }
+        }
+        best.unwrap().1.clone()
+    }
+}
@@ -0,0 +1,25 @@
+use std::{fs, io, path::PathBuf};
+
+/// Path of a file in the directory the game keeps its data in, creating the
+/// directory if needed. `MASTERMIND_DATA_DIR` overrides the platform default.
+pub fn data_file(name: &str) -> io::Result<PathBuf> {
+    let dir = match std::env::var_os("MASTERMIND_DATA_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::data_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?
+            .join("mastermind"),
+    };
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(name))
+}
+
+/// Keeps the data of the tests out of the player's, in a directory of its own
+/// for every test run.
+#[cfg(test)]
+pub fn use_test_dir() {
+    static TEST_DIR: std::sync::Once = std::sync::Once::new();
+    TEST_DIR.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("mastermind-test-{}", std::process::id()));
+        std::env::set_var("MASTERMIND_DATA_DIR", dir);
+    });
+}
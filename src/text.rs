@@ -0,0 +1,98 @@
+//! One game over stdin and stdout for bots and scripts: every line read is a
+//! guess like `RGBY`, every line printed an event of the game.
+
+use std::io::{self, BufRead};
+
+use mastermind::{score, Code};
+use rand::{rngs::StdRng, SeedableRng};
+use serde_json::{json, Value};
+
+use crate::options::{Options, TextOutput};
+
+/// Prints the feedback for every guess on stdin until the secret is cracked,
+/// the guesses run out or stdin ends. Returns whether the secret was cracked.
+pub fn run(options: &Options, output: TextOutput) -> Result<bool, String> {
+    let rules = &options.rules;
+    let print = |plain: String, json: Value| match output {
+        TextOutput::Plain => println!("{plain}"),
+        TextOutput::Json => println!("{json}"),
+    };
+    // the same secret the window starts with for this seed
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let secret = Code::random(rules, &mut StdRng::seed_from_u64(seed));
+    print(
+        format!(
+            "seed {seed}, {} pegs, {} colors, {} guesses",
+            rules.code_length, rules.number_of_colors, options.number_of_guesses
+        ),
+        json!({
+            "event": "start",
+            "seed": seed,
+            "pegs": rules.code_length,
+            "colors": rules.number_of_colors,
+            "guesses": options.number_of_guesses,
+            "duplicates": rules.allow_duplicates,
+            "unique_guesses": rules.reject_duplicate_guesses,
+            "blanks": rules.allow_blanks,
+        }),
+    );
+
+    let mut guesses = 0;
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|error| error.to_string())?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let guess = line.parse::<Code>().map_err(|error| error.to_string());
+        let guess = match guess.and_then(|guess| match rules.check_guess(&guess) {
+            Ok(()) => Ok(guess),
+            Err(error) => Err(error.to_string()),
+        }) {
+            Ok(guess) => guess,
+            // an invalid line doesn't cost a guess
+            Err(message) => {
+                print(
+                    format!("invalid guess '{line}': {message}"),
+                    json!({"event": "invalid", "line": line, "message": message}),
+                );
+                continue;
+            }
+        };
+
+        guesses += 1;
+        let feedback = score(&secret, &guess);
+        print(
+            format!(
+                "{guesses}: {guess}  black {} white {}",
+                feedback.black, feedback.white
+            ),
+            json!({
+                "event": "feedback",
+                "guess": guess.to_string(),
+                "number": guesses,
+                "black": feedback.black,
+                "white": feedback.white,
+            }),
+        );
+        if feedback.black == rules.code_length {
+            print(
+                format!("won in {guesses} guesses"),
+                json!({"event": "won", "guesses": guesses, "secret": secret.to_string()}),
+            );
+            return Ok(true);
+        }
+        if guesses == options.number_of_guesses {
+            print(
+                format!("lost, the secret was {secret}"),
+                json!({"event": "lost", "guesses": guesses, "secret": secret.to_string()}),
+            );
+            return Ok(false);
+        }
+    }
+    print(
+        format!("stopped after {guesses} guesses, the secret was {secret}"),
+        json!({"event": "stopped", "guesses": guesses, "secret": secret.to_string()}),
+    );
+    Ok(false)
+}
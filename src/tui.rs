@@ -0,0 +1,265 @@
+//! The terminal frontend: the same `Game` as in the window, drawn with ANSI
+//! colors and driven by terminal key events, so it also plays over SSH.
+
+use std::{
+    io::{self, Stdout, Write},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{self, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use mastermind::Colors;
+use piston_window::{types::Color, Key};
+
+use crate::{
+    game::{Game, View},
+    guess::{color_key_label, DrawColor},
+    BACK_COLOR, COLOR_GAMEOVER, COLOR_GREEN, COLOR_RED, COLOR_SUCCESS, COLOR_TEXT,
+};
+
+// how long to wait for a key before the game is updated again
+const TICK: Duration = Duration::from_millis(50);
+
+/// Plays in the terminal until Esc or Ctrl-C, then prints the game log.
+pub fn run(mut game: Game) -> io::Result<()> {
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, Hide)?;
+    let result = play(&mut game, &mut out);
+    // give the terminal back as it was, also if playing failed
+    execute!(out, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    for line in game.take_log() {
+        println!("{line}");
+    }
+    result
+}
+
+fn play(game: &mut Game, out: &mut Stdout) -> io::Result<()> {
+    let title = game.title();
+    let mut last_update = Instant::now();
+    loop {
+        draw(out, &title, &game.view())?;
+        if event::poll(TICK)? {
+            if let Event::Key(key) = event::read()? {
+                let control = key.modifiers.contains(KeyModifiers::CONTROL);
+                match key.code {
+                    _ if key.kind != KeyEventKind::Press => {}
+                    KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('c') if control => return Ok(()),
+                    code => {
+                        if let Some(key) = to_key(code) {
+                            game.key_pressed(key)
+                        }
+                    }
+                }
+            }
+        }
+        let now = Instant::now();
+        game.update((now - last_update).as_secs_f64());
+        last_update = now;
+    }
+}
+
+/// The key the window would get for `code`.
+fn to_key(code: KeyCode) -> Option<Key> {
+    match code {
+        KeyCode::Left => Some(Key::Left),
+        KeyCode::Right => Some(Key::Right),
+        KeyCode::Up => Some(Key::Up),
+        KeyCode::Down => Some(Key::Down),
+        KeyCode::Enter => Some(Key::Return),
+        KeyCode::Backspace => Some(Key::Backspace),
+        KeyCode::Delete => Some(Key::Delete),
+        KeyCode::Char('-') => Some(Key::Minus),
+        KeyCode::Char('=') => Some(Key::Equals),
+        // piston numbers the letter and digit keys by their ASCII code
+        KeyCode::Char(c) if c.is_ascii_alphanumeric() => {
+            Some(Key::from(c.to_ascii_lowercase() as u32))
+        }
+        _ => None,
+    }
+}
+
+fn draw(out: &mut Stdout, title: &str, view: &View) -> io::Result<()> {
+    queue!(out, MoveTo(0, 0))?;
+    let code_length = view.input.len();
+    // side with row numbers, pegs, pins, side
+    let width = usize::max(
+        4 + 3 * code_length + 2 + code_length + 1,
+        4 + 3 * view.palette.len(),
+    );
+    let mut screen = Screen {
+        out,
+        row: 0,
+        column: 0,
+        width,
+        background: None,
+    };
+    screen.text(COLOR_TEXT, title)?;
+    screen.end_line()?;
+    screen.end_line()?;
+
+    if let Some(overlay) = &view.overlay {
+        for line in overlay {
+            screen.text(COLOR_TEXT, line)?;
+            screen.end_line()?;
+        }
+        return screen.finish();
+    }
+
+    screen.set_background(Some(BACK_COLOR))?;
+    screen.end_line()?;
+    screen.text(COLOR_TEXT, "    ")?;
+    for peg in &view.secret {
+        screen.peg(*peg, false)?;
+    }
+    screen.end_line()?;
+    screen.end_line()?;
+
+    for (i, (pegs, pins)) in view.rows.iter().enumerate() {
+        match &view.cover {
+            Some(cover) if i == view.rows.len() / 2 => {
+                screen.text(COLOR_TEXT, &format!(" {cover}"))?
+            }
+            Some(_) => {}
+            None => {
+                let mark = match view.marked_rows.contains(&i) {
+                    true => "▌",
+                    false => " ",
+                };
+                screen.text(COLOR_RED, mark)?;
+                screen.text(COLOR_TEXT, &format!("{:>2} ", i + 1))?;
+                for peg in pegs {
+                    screen.peg(*peg, false)?;
+                }
+                screen.text(COLOR_TEXT, "  ")?;
+                for pin in pins {
+                    screen.pin(*pin)?;
+                }
+            }
+        }
+        screen.end_line()?;
+    }
+    screen.end_line()?;
+
+    screen.text(COLOR_TEXT, "    ")?;
+    for (i, peg) in view.input.iter().enumerate() {
+        screen.peg(*peg, view.position == i)?;
+    }
+    let enter = match view.ready {
+        true => COLOR_GREEN,
+        false => COLOR_RED,
+    };
+    match view.position == code_length {
+        true => screen.text(enter, " [OK]")?,
+        false => screen.text(enter, "  OK ")?,
+    }
+    screen.end_line()?;
+    screen.text(COLOR_TEXT, "    ")?;
+    for (i, color) in view.palette.iter().enumerate() {
+        let label = color_key_label(i).unwrap_or(' ');
+        screen.text(COLOR_TEXT, &label.to_string())?;
+        screen.text(color.return_color(), "● ")?;
+    }
+    screen.end_line()?;
+    screen.end_line()?;
+    screen.set_background(None)?;
+
+    let status_color = match (view.won, view.over) {
+        (true, _) => COLOR_SUCCESS,
+        (_, true) => COLOR_GAMEOVER,
+        _ => COLOR_TEXT,
+    };
+    screen.end_line()?;
+    screen.text(status_color, &view.status)?;
+    screen.end_line()?;
+    screen.text(COLOR_TEXT, &view.status_detail)?;
+    screen.end_line()?;
+    screen.end_line()?;
+    screen.text(COLOR_TEXT, "H HELP, ESC QUIT")?;
+    screen.end_line()?;
+    screen.finish()
+}
+
+/// Writes the screen line by line, lines on a background are padded to the
+/// same width so the board is a rectangle.
+struct Screen<'a> {
+    out: &'a mut Stdout,
+    row: u16,
+    column: usize,
+    width: usize,
+    background: Option<Color>,
+}
+
+impl Screen<'_> {
+    fn text(&mut self, color: Color, text: &str) -> io::Result<()> {
+        queue!(self.out, SetForegroundColor(rgb(color)), Print(text))?;
+        self.column += text.chars().count();
+        Ok(())
+    }
+
+    fn peg(&mut self, color: Colors, selected: bool) -> io::Result<()> {
+        let glyph = match color {
+            Colors::Empty => "·",
+            Colors::Secret => "?",
+            // a blank is an open hole, like in the window
+            Colors::Blank => "○",
+            _ => "●",
+        };
+        let (open, close) = match selected {
+            true => ("[", "]"),
+            false => (" ", " "),
+        };
+        self.text(COLOR_TEXT, open)?;
+        self.text(color.return_color(), glyph)?;
+        self.text(COLOR_TEXT, close)
+    }
+
+    fn pin(&mut self, color: Colors) -> io::Result<()> {
+        match color {
+            Colors::Empty => self.text(color.return_color(), "·"),
+            _ => self.text(color.return_color(), "●"),
+        }
+    }
+
+    fn set_background(&mut self, background: Option<Color>) -> io::Result<()> {
+        self.background = background;
+        match background {
+            Some(color) => queue!(self.out, SetBackgroundColor(rgb(color))),
+            None => queue!(self.out, ResetColor),
+        }
+    }
+
+    fn end_line(&mut self) -> io::Result<()> {
+        if self.background.is_some() {
+            let padding = self.width.saturating_sub(self.column);
+            queue!(self.out, Print(" ".repeat(padding)))?;
+        }
+        self.row += 1;
+        self.column = 0;
+        queue!(
+            self.out,
+            ResetColor,
+            Clear(ClearType::UntilNewLine),
+            MoveTo(0, self.row)
+        )?;
+        self.set_background(self.background)
+    }
+
+    /// Clears what is left of the last frame.
+    fn finish(&mut self) -> io::Result<()> {
+        queue!(self.out, ResetColor, Clear(ClearType::FromCursorDown))?;
+        self.out.flush()
+    }
+}
+
+fn rgb(color: Color) -> style::Color {
+    let [r, g, b, _] = color.map(|channel| (channel * 255.0) as u8);
+    style::Color::Rgb { r, g, b }
+}
@@ -0,0 +1,27 @@
+use mastermind::{score, Code, Feedback};
+
+fn feedback(secret: &str, guess: &str) -> Feedback {
+    score(&secret.parse().unwrap(), &guess.parse().unwrap())
+}
+
+#[test]
+fn black_and_white_pins() {
+    assert_eq!(feedback("RGBY", "RGBY"), Feedback { black: 4, white: 0 });
+    assert_eq!(feedback("RGBY", "YBGR"), Feedback { black: 0, white: 4 });
+    assert_eq!(feedback("RRGG", "RGRB"), Feedback { black: 1, white: 2 });
+    assert_eq!(feedback("RGBY", "KWKW"), Feedback { black: 0, white: 0 });
+}
+
+#[test]
+fn blank_is_scored_like_a_color() {
+    assert_eq!(feedback("R_B_", "R_B_"), Feedback { black: 4, white: 0 });
+    assert_eq!(feedback("R_B_", "_R_B"), Feedback { black: 0, white: 4 });
+    assert_eq!(feedback("R_BY", "__RR"), Feedback { black: 1, white: 1 });
+}
+
+#[test]
+fn codes_are_written_one_letter_per_peg() {
+    let code: Code = "rgb_".parse().unwrap();
+    assert_eq!(code.to_string(), "RGB_");
+    assert!("RGX".parse::<Code>().is_err());
+}
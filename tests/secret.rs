@@ -0,0 +1,78 @@
+use mastermind::{Code, CodeError, Colors, Rules, MAX_COLORS, MIN_COLORS};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn every_playable_color_can_be_drawn() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for number_of_colors in MIN_COLORS..=MAX_COLORS {
+        let rules = Rules {
+            number_of_colors,
+            ..Rules::default()
+        };
+        let mut drawn = vec![];
+        for _ in 0..1000 {
+            drawn.extend_from_slice(Code::random(&rules, &mut rng).pegs());
+        }
+        for color in rules.playable_colors() {
+            assert!(drawn.contains(&color), "{color:?} was never drawn");
+        }
+    }
+}
+
+#[test]
+fn secret_only_uses_playable_colors() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let rules = Rules::default();
+    let playable_colors = rules.playable_colors();
+    for _ in 0..1000 {
+        let secret = Code::random(&rules, &mut rng);
+        assert_eq!(secret.len(), rules.code_length);
+        assert!(secret.pegs().iter().all(|c| playable_colors.contains(c)));
+        assert!(!secret.pegs().contains(&Colors::Empty));
+    }
+}
+
+#[test]
+fn white_is_playable_in_the_standard_game() {
+    assert!(Rules::default().playable_colors().contains(&Colors::White));
+}
+
+#[test]
+fn secret_without_duplicates_repeats_no_color() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let rules = Rules {
+        allow_duplicates: false,
+        ..Rules::default()
+    };
+    for _ in 0..1000 {
+        assert!(!Code::random(&rules, &mut rng).has_duplicates());
+    }
+}
+
+#[test]
+fn guesses_only_repeat_colors_if_the_rules_allow_it() {
+    let rules = Rules {
+        allow_duplicates: false,
+        ..Rules::default()
+    };
+    let guess: Code = "RRBG".parse().unwrap();
+    assert_eq!(rules.check_guess(&guess), Ok(()));
+    assert_eq!(rules.check_secret(&guess), Err(CodeError::Duplicates));
+
+    let rules = Rules {
+        reject_duplicate_guesses: true,
+        ..rules
+    };
+    assert_eq!(rules.check_guess(&guess), Err(CodeError::Duplicates));
+    assert_eq!(
+        rules.check_guess(&"RBG".parse().unwrap()),
+        Err(CodeError::Length {
+            expected: 4,
+            found: 3
+        })
+    );
+    assert_eq!(
+        rules.check_guess(&"RBGP".parse().unwrap()),
+        Err(CodeError::Color(Colors::Pink))
+    );
+}
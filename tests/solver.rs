@@ -0,0 +1,47 @@
+use mastermind::{
+    score,
+    solver::{all_codes, Solver},
+    Code, Rules,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn standard_game_has_1296_codes() {
+    assert_eq!(all_codes(&Rules::default()).unwrap().len(), 1296);
+    let rules = Rules {
+        allow_duplicates: false,
+        ..Rules::default()
+    };
+    assert_eq!(all_codes(&rules).unwrap().len(), 360);
+}
+
+#[test]
+fn knuth_opens_with_1122() {
+    let solver = Solver::new(&Rules::default()).unwrap();
+    assert_eq!(solver.next_guess().unwrap().to_string(), "RRBB");
+}
+
+#[test]
+fn solves_standard_game_in_five_guesses() {
+    let rules = Rules::default();
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..20 {
+        let secret = Code::random(&rules, &mut rng);
+        let rows = Solver::new(&rules).unwrap().solve(&secret);
+        assert!(rows.len() <= 5, "{secret} took {} guesses", rows.len());
+        assert_eq!(rows.last().unwrap().0, secret);
+        for (guess, feedback) in rows {
+            assert_eq!(score(&secret, &guess), feedback);
+        }
+    }
+}
+
+#[test]
+fn feedback_narrows_the_candidates() {
+    let mut solver = Solver::new(&Rules::default()).unwrap();
+    let secret: Code = "RGBY".parse().unwrap();
+    let guess: Code = "RRBB".parse().unwrap();
+    solver.add_feedback(&guess, score(&secret, &guess));
+    assert!(solver.candidates().contains(&secret));
+    assert!(solver.candidates().len() < 1296);
+}